@@ -0,0 +1,356 @@
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+use crate::constraint::{Boxes, Columns, Constraint, Rows};
+use crate::grid::{Grid, Shape};
+
+/** Bit `d - 1` is set for each digit `d` in a set. */
+pub(crate) type Mask = u32;
+
+/**
+ * The cell structure of one shape under a set of rules, computed once and
+ * shared by every board and candidate grid that uses it.
+ */
+pub(crate) struct Layout {
+    pub(crate) shape: Shape,
+    pub(crate) size: usize,
+    pub(crate) cells: usize,
+    /** Every digit `1..=size`. */
+    pub(crate) all: Mask,
+    box_of: Vec<usize>,
+    /**
+     * The cells of every unit, in the order the rules list them; for the
+     * classic rules rows first, then columns, then boxes.
+     */
+    pub(crate) units: Vec<Vec<usize>>,
+    /** The units that contain each cell. */
+    pub(crate) units_of: Vec<Vec<usize>>,
+    /**
+     * The constraints that narrow candidates beyond the units, adapted to
+     * the units with `Constraint::within`.
+     */
+    pub(crate) eliminators: Vec<Arc<dyn Constraint>>,
+}
+
+impl fmt::Debug for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Layout({}, {} units)", self.shape, self.units.len())
+    }
+}
+
+impl PartialEq for Layout {
+    fn eq(&self, other: &Self) -> bool {
+        self.shape == other.shape && self.units == other.units
+    }
+}
+
+impl Eq for Layout {}
+
+impl Layout {
+    /**
+     * Collects the units of a shape from the regions of every constraint.
+     */
+    pub(crate) fn new(shape: Shape, constraints: &[Arc<dyn Constraint>]) -> Self {
+        let size = shape.size();
+        let cells = shape.cells();
+        let box_of: Vec<usize> = (0..cells)
+            .map(|cell| shape.box_of(cell / size, cell % size))
+            .collect();
+        let units: Vec<Vec<usize>> = constraints
+            .iter()
+            .flat_map(|constraint| constraint.regions(shape))
+            .collect();
+        let mut units_of = vec![Vec::new(); cells];
+        for (unit, members) in units.iter().enumerate() {
+            for &cell in members {
+                units_of[cell].push(unit);
+            }
+        }
+        Layout {
+            shape,
+            size,
+            cells,
+            all: (1 << size) - 1,
+            box_of,
+            eliminators: constraints
+                .iter()
+                .filter(|constraint| constraint.eliminates())
+                .map(|constraint| {
+                    constraint
+                        .within(shape, &units)
+                        .unwrap_or_else(|| Arc::clone(constraint))
+                })
+                .collect(),
+            units,
+            units_of,
+        }
+    }
+
+    /** Returns the number of units. */
+    pub(crate) fn unit_count(&self) -> usize {
+        self.units.len()
+    }
+
+    /** Returns the row that contains a cell. */
+    pub(crate) fn row(&self, cell: usize) -> usize {
+        cell / self.size
+    }
+
+    /** Returns the column that contains a cell. */
+    pub(crate) fn col(&self, cell: usize) -> usize {
+        cell % self.size
+    }
+
+    /** Returns the box of the shape that contains a cell. */
+    pub(crate) fn box_index(&self, cell: usize) -> usize {
+        self.box_of[cell]
+    }
+
+    /** Returns the (row, column) of a cell. */
+    pub(crate) fn coordinates(&self, cell: usize) -> (usize, usize) {
+        (cell / self.size, cell % self.size)
+    }
+}
+
+/** Box dimensions are at most `Shape::MAX_SIZE / 2`. */
+const MAX_SIDE: usize = Shape::MAX_SIZE / 2 + 1;
+
+static LAYOUTS: [[OnceLock<Arc<Layout>>; MAX_SIDE]; MAX_SIDE] =
+    [const { [const { OnceLock::new() }; MAX_SIDE] }; MAX_SIDE];
+
+/**
+ * Returns the layout of a shape under the classic rules, building it on
+ * first use.
+ */
+pub(crate) fn classic_layout(shape: Shape) -> Arc<Layout> {
+    Arc::clone(layout(shape))
+}
+
+/**
+ * Returns the layout of a shape under the classic rules, as `classic_layout`
+ * does, borrowed for the life of the program.
+ */
+pub(crate) fn layout(shape: Shape) -> &'static Arc<Layout> {
+    LAYOUTS[shape.box_rows()][shape.box_cols()].get_or_init(|| {
+        let classic: [Arc<dyn Constraint>; 3] =
+            [Arc::new(Rows), Arc::new(Columns), Arc::new(Boxes)];
+        Arc::new(Layout::new(shape, &classic))
+    })
+}
+
+/**
+ * Returns the bit for a digit.
+ */
+pub(crate) fn bit(digit: u8) -> Mask {
+    1 << (digit - 1)
+}
+
+/**
+ * Iterates over the digits in a mask, lowest first.
+ */
+pub(crate) fn digits(mut mask: Mask) -> impl Iterator<Item = u8> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+        let digit = mask.trailing_zeros() as u8 + 1;
+        mask &= mask - 1;
+        Some(digit)
+    })
+}
+
+/**
+ * Search state for the backtracking engine.
+ *
+ * Alongside the cell values it keeps the digits placed in each unit,
+ * updated incrementally by `place` and `unplace`, so the candidates of a cell
+ * are a few lookups instead of a rescan of its peers.
+ */
+#[derive(Clone)]
+pub(crate) struct Board {
+    layout: Arc<Layout>,
+    cells: Vec<u8>,
+    /** The digits placed in each unit. */
+    used: Vec<Mask>,
+}
+
+impl Board {
+    /**
+     * Loads the givens of a grid under the classic rules.
+     * @return The board, or `None` if two givens share a row, column or box.
+     */
+    pub(crate) fn new(grid: &Grid) -> Option<Self> {
+        Board::with_layout(grid, classic_layout(grid.shape()))
+    }
+
+    /**
+     * Loads the givens of a grid under the rules a layout was built from.
+     * @return The board, or `None` if the givens break a rule.
+     */
+    pub(crate) fn with_layout(grid: &Grid, layout: Arc<Layout>) -> Option<Self> {
+        let mut board = Board {
+            cells: vec![0; layout.cells],
+            used: vec![0; layout.unit_count()],
+            layout,
+        };
+        for (cell, value) in grid.to_values().into_iter().enumerate() {
+            if value != 0 {
+                if board.candidates(cell) & bit(value) == 0 {
+                    return None;
+                }
+                board.place(cell, value);
+            }
+        }
+        Some(board)
+    }
+
+    /**
+     * Returns the digits that can be placed in a blank cell.
+     */
+    pub(crate) fn candidates(&self, cell: usize) -> Mask {
+        let layout = &*self.layout;
+        let used = layout.units_of[cell]
+            .iter()
+            .fold(0, |used, &unit| used | self.used[unit]);
+        let candidates = !used & layout.all;
+        layout
+            .eliminators
+            .iter()
+            .fold(candidates, |candidates, constraint| {
+                constraint.eliminate(&self.cells, cell, candidates)
+            })
+    }
+
+    /**
+     * Places a digit in a blank cell. The caller must have checked that it is
+     * a candidate.
+     */
+    pub(crate) fn place(&mut self, cell: usize, digit: u8) {
+        let bit = bit(digit);
+        self.cells[cell] = digit;
+        for &unit in &self.layout.units_of[cell] {
+            self.used[unit] |= bit;
+        }
+    }
+
+    /**
+     * Blanks a cell filled by `place`.
+     */
+    pub(crate) fn unplace(&mut self, cell: usize) {
+        let bit = !bit(self.cells[cell]);
+        self.cells[cell] = 0;
+        for &unit in &self.layout.units_of[cell] {
+            self.used[unit] &= bit;
+        }
+    }
+
+    /**
+     * Finds the blank cell with the fewest candidates (minimum remaining
+     * values), stopping early at a cell with none or one.
+     *
+     * When every cell has several candidates, a digit with a single place
+     * left in some unit is forced there instead, and a digit with no place
+     * left is a dead end, reported as a cell without candidates. Large grids
+     * depend on this: their cells rarely run down to one candidate.
+     *
+     * @return The cell and the digits to try there, or `None` if the board
+     * is full.
+     */
+    pub(crate) fn most_constrained(&self) -> Option<(usize, Mask)> {
+        let mut best = None;
+        let mut best_count = u32::MAX;
+        for (cell, &value) in self.cells.iter().enumerate() {
+            if value != 0 {
+                continue;
+            }
+            let candidates = self.candidates(cell);
+            let count = candidates.count_ones();
+            if count < best_count {
+                best = Some((cell, candidates));
+                best_count = count;
+                if count <= 1 {
+                    break;
+                }
+            }
+        }
+        if best_count > 1 {
+            if let Some(forced) = self.hidden_single() {
+                return Some(forced);
+            }
+        }
+        best
+    }
+
+    /**
+     * Finds a digit that fits in only one blank cell of a unit, or one that
+     * fits in none.
+     * @return The cell and the digit's bit, or a blank cell of the unit with
+     * no digits for a digit that no longer fits, or `None` if no unit forces
+     * anything.
+     */
+    fn hidden_single(&self) -> Option<(usize, Mask)> {
+        let layout = &*self.layout;
+        for (unit, cells) in layout.units.iter().enumerate() {
+            // Only regions as large as the grid must hold every digit.
+            if cells.len() < layout.size {
+                continue;
+            }
+            let (mut once, mut twice) = (0, 0);
+            for &cell in cells {
+                if self.cells[cell] == 0 {
+                    let candidates = self.candidates(cell);
+                    twice |= once & candidates;
+                    once |= candidates;
+                }
+            }
+            let missing = layout.all & !self.used[unit];
+            let single = missing & once & !twice;
+            if missing & !once == 0 && single == 0 {
+                continue;
+            }
+            let bit = match missing & !once {
+                0 => single & single.wrapping_neg(),
+                _ => 0,
+            };
+            let blank = |&cell: &usize| self.cells[cell] == 0;
+            let cell = match bit {
+                0 => cells.iter().copied().find(|cell| blank(cell))?,
+                _ => cells
+                    .iter()
+                    .copied()
+                    .find(|cell| blank(cell) && self.candidates(*cell) & bit != 0)?,
+            };
+            return Some((cell, bit));
+        }
+        None
+    }
+
+    /**
+     * Converts the board back into a grid.
+     */
+    pub(crate) fn to_grid(&self) -> Grid {
+        Grid::with_values(self.layout.shape, &self.cells).expect("board digits are in range")
+    }
+}
+
+/**
+ * Finds the filled cells whose digit is repeated elsewhere in one of their
+ * units, such as their row, column or box.
+ * @param layout The units of the grid's shape under its rules.
+ * @return One flag per cell, in row-major order.
+ */
+pub(crate) fn conflicts(grid: &Grid, layout: &Layout) -> Vec<bool> {
+    let values = grid.to_values();
+    let mut conflicting = vec![false; values.len()];
+    for unit in &layout.units {
+        for (i, &a) in unit.iter().enumerate() {
+            for &b in &unit[i + 1..] {
+                if values[a] != 0 && values[a] == values[b] {
+                    conflicting[a] = true;
+                    conflicting[b] = true;
+                }
+            }
+        }
+    }
+    conflicting
+}
@@ -0,0 +1,397 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rand::Rng;
+
+use crate::draw::{border_width, draw_grid, Overlay};
+use crate::generator::{GenerationError, Generator, PuzzleOptions};
+use crate::grid::Shape;
+use crate::pdf::{text_width, Document, Page};
+use crate::puzzle::Puzzle;
+use crate::rating::Tier;
+
+/**
+ * A paper size for booklets.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PageSize {
+    /** 210 x 297 mm. */
+    #[default]
+    A4,
+    /** 8.5 x 11 in. */
+    Letter,
+}
+
+impl PageSize {
+    /** Every page size. */
+    pub const ALL: [PageSize; 2] = [PageSize::A4, PageSize::Letter];
+
+    /**
+     * Returns the short name used on the command line.
+     */
+    pub fn name(self) -> &'static str {
+        match self {
+            PageSize::A4 => "a4",
+            PageSize::Letter => "letter",
+        }
+    }
+
+    /**
+     * Returns the width and height in points.
+     */
+    pub fn points(self) -> (f64, f64) {
+        match self {
+            PageSize::A4 => (595.28, 841.89),
+            PageSize::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+impl fmt::Display for PageSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for PageSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PageSize::ALL
+            .into_iter()
+            .find(|size| size.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown page size '{}'", s))
+    }
+}
+
+/**
+ * What goes into a booklet and how it is laid out.
+ */
+#[derive(Debug, Clone)]
+pub struct BookletOptions {
+    count: usize,
+    shape: Shape,
+    tiers: Vec<Tier>,
+    per_page: usize,
+    page_size: PageSize,
+    title: String,
+    max_attempts: usize,
+}
+
+impl BookletOptions {
+    /**
+     * Creates options for `count` 9x9 puzzles spread over the easy to expert
+     * tiers, four per A4 page. Harder tiers are rare among random grids, so
+     * up to 1000 are tried per puzzle.
+     */
+    pub fn new(count: usize) -> Self {
+        BookletOptions {
+            count,
+            shape: Shape::CLASSIC,
+            tiers: vec![Tier::Easy, Tier::Medium, Tier::Hard, Tier::Expert],
+            per_page: 4,
+            page_size: PageSize::A4,
+            title: "Sudoku".to_string(),
+            max_attempts: 1000,
+        }
+    }
+
+    /**
+     * Sets the shape of the grids, such as 2x2 boxes for 4x4 puzzles.
+     */
+    pub fn shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /**
+     * Sets the tiers to draw puzzles from. Puzzles are split as evenly as
+     * possible between them and ordered from the easiest tier.
+     */
+    pub fn tiers(mut self, tiers: &[Tier]) -> Self {
+        let mut tiers = tiers.to_vec();
+        tiers.sort();
+        tiers.dedup();
+        self.tiers = tiers;
+        self
+    }
+
+    /**
+     * Sets how many puzzles share a page (at least 1). Generating the booklet
+     * fails if the grids would get too small to read.
+     */
+    pub fn per_page(mut self, per_page: usize) -> Self {
+        self.per_page = per_page.max(1);
+        self
+    }
+
+    /**
+     * Sets the paper size.
+     */
+    pub fn page_size(mut self, page_size: PageSize) -> Self {
+        self.page_size = page_size;
+        self
+    }
+
+    /**
+     * Sets the title printed at the top of every page.
+     */
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /**
+     * Sets the fresh grids the generator may try per puzzle.
+     */
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+}
+
+/**
+ * A set of puzzles laid out for print, with an answer key at the back.
+ */
+#[derive(Debug, Clone)]
+pub struct Booklet {
+    options: BookletOptions,
+    puzzles: Vec<(Tier, Puzzle)>,
+}
+
+/** Space around the printed area of a page, in points. */
+const MARGIN: f64 = 48.0;
+/** Height of the title line at the top and the page number at the bottom. */
+const RUNNING: f64 = 24.0;
+/** Height reserved above each grid for its label. */
+const LABEL: f64 = 20.0;
+/** Font size of the puzzle number above each grid. */
+const PUZZLE_LABEL: f64 = 13.0;
+/**
+ * Smallest cell side of a puzzle grid, in points; digits are set at about
+ * two thirds of it.
+ */
+const MIN_CELL: f64 = 8.0;
+/** Columns and rows of answers on an answer-key page. */
+const ANSWER_LAYOUT: (usize, usize) = (3, 4);
+
+impl Booklet {
+    /**
+     * Generates the puzzles of a booklet, easiest tier first.
+     * @param generator The source of puzzles.
+     * @param options What to generate.
+     * @return The booklet, or the first puzzle that could not be generated,
+     * `EmptyBooklet` if the options ask for no puzzles or no tiers, or
+     * `CrowdedPage` if the puzzles on a page would be too small to read.
+     */
+    pub fn generate<R: Rng>(
+        generator: &mut Generator<R>,
+        options: &BookletOptions,
+    ) -> Result<Self, GenerationError> {
+        if options.count == 0 || options.tiers.is_empty() {
+            return Err(GenerationError::EmptyBooklet);
+        }
+        if puzzle_cell(options, options.per_page) < MIN_CELL {
+            let max = (1..options.per_page)
+                .rev()
+                .find(|&per_page| puzzle_cell(options, per_page) >= MIN_CELL)
+                .unwrap_or(1);
+            return Err(GenerationError::CrowdedPage {
+                per_page: options.per_page,
+                max,
+            });
+        }
+        let mut puzzles = Vec::with_capacity(options.count);
+        let tiers = options.tiers.len();
+        for (index, &tier) in options.tiers.iter().enumerate() {
+            let share = options.count / tiers + usize::from(index < options.count % tiers);
+            let puzzle_options = PuzzleOptions::new(options.shape.cells())
+                .shape(options.shape)
+                .difficulty(tier)
+                .max_attempts(options.max_attempts);
+            for _ in 0..share {
+                puzzles.push((tier, generator.generate(&puzzle_options)?));
+            }
+        }
+        Ok(Booklet {
+            options: options.clone(),
+            puzzles,
+        })
+    }
+
+    /**
+     * Returns the puzzles in print order with their tiers.
+     */
+    pub fn puzzles(&self) -> &[(Tier, Puzzle)] {
+        &self.puzzles
+    }
+
+    /**
+     * Returns the number of pages: the puzzle pages followed by the answer
+     * key.
+     */
+    pub fn pages(&self) -> usize {
+        let (cols, rows) = ANSWER_LAYOUT;
+        self.puzzles.len().div_ceil(self.options.per_page)
+            + self.puzzles.len().div_ceil(cols * rows)
+    }
+
+    /**
+     * Lays the booklet out as a PDF document.
+     */
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let (width, height) = self.options.page_size.points();
+        let mut document = Document::new(width, height, &self.options.title);
+        let per_page = self.options.per_page;
+        let layout = page_layout(per_page);
+        let total = self.pages();
+        let size = self.options.shape.size();
+        let mut number = 0;
+
+        for chunk in self.puzzles.chunks(per_page) {
+            number += 1;
+            let page = self.start_page(&mut document, number, total);
+            for (slot, (tier, puzzle)) in chunk.iter().enumerate() {
+                let index = (number - 1) * per_page + slot + 1;
+                let area = page_area(width, height);
+                let (x, y, cell) = slot_grid(area, layout, slot, size, PUZZLE_LABEL);
+                page.label(x, y - 7.0, PUZZLE_LABEL, true, &format!("Puzzle {}", index));
+                let tier_name = label(*tier);
+                let right = x + size as f64 * cell;
+                page.label(
+                    right - text_width(&tier_name, 11.0),
+                    y - 7.0,
+                    11.0,
+                    false,
+                    &tier_name,
+                );
+                draw_grid(
+                    page,
+                    (x, y),
+                    cell,
+                    puzzle.givens(),
+                    puzzle.givens(),
+                    None,
+                    Overlay::default(),
+                );
+            }
+        }
+
+        let (cols, rows) = ANSWER_LAYOUT;
+        for (chunk_index, chunk) in self.puzzles.chunks(cols * rows).enumerate() {
+            number += 1;
+            let page = self.start_page(&mut document, number, total);
+            let (left, top, area_width, area_height) = page_area(width, height);
+            page.label(left, top + 18.0, 18.0, true, "Answers");
+            let area = (left, top + 28.0, area_width, area_height - 28.0);
+            for (slot, (tier, puzzle)) in chunk.iter().enumerate() {
+                let index = chunk_index * cols * rows + slot + 1;
+                let (x, y, cell) = slot_grid(area, ANSWER_LAYOUT, slot, size, 9.0);
+                page.label(
+                    x,
+                    y - 6.0,
+                    9.0,
+                    true,
+                    &format!("{} ({})", index, label(*tier)),
+                );
+                draw_grid(
+                    page,
+                    (x, y),
+                    cell,
+                    puzzle.givens(),
+                    puzzle.solution(),
+                    None,
+                    Overlay::default(),
+                );
+            }
+        }
+        document.finish()
+    }
+
+    /**
+     * Adds a page with the running title and its page number.
+     */
+    fn start_page<'a>(
+        &self,
+        document: &'a mut Document,
+        number: usize,
+        total: usize,
+    ) -> &'a mut Page {
+        let (width, height) = self.options.page_size.points();
+        let page = document.add_page();
+        page.label(MARGIN, MARGIN, 10.0, false, &self.options.title);
+        page.centered_label(
+            width / 2.0,
+            height - MARGIN + 10.0,
+            10.0,
+            false,
+            &format!("{} / {}", number, total),
+        );
+        page
+    }
+}
+
+/**
+ * Returns the name of a tier as printed, capitalized.
+ */
+fn label(tier: Tier) -> String {
+    let mut name = tier.name().to_string();
+    name[..1].make_ascii_uppercase();
+    name
+}
+
+/**
+ * Returns the columns and rows of puzzles on a page.
+ */
+fn page_layout(per_page: usize) -> (usize, usize) {
+    match per_page {
+        1..=3 => (1, per_page),
+        _ => (2, per_page.div_ceil(2)),
+    }
+}
+
+/**
+ * Returns the cell size of the puzzle grids with a number of them per page.
+ */
+fn puzzle_cell(options: &BookletOptions, per_page: usize) -> f64 {
+    let (width, height) = options.page_size.points();
+    let layout = page_layout(per_page);
+    let size = options.shape.size();
+    slot_grid(page_area(width, height), layout, 0, size, PUZZLE_LABEL).2
+}
+
+/**
+ * Returns the area left for grids on a page: left, top, width and height.
+ */
+fn page_area(width: f64, height: f64) -> (f64, f64, f64, f64) {
+    (
+        MARGIN,
+        MARGIN + RUNNING,
+        width - 2.0 * MARGIN,
+        height - 2.0 * (MARGIN + RUNNING),
+    )
+}
+
+/**
+ * Places a grid in one slot of a page area split into `cols` by `rows`
+ * slots, filled row by row.
+ * @param size The number of rows and columns of the grid.
+ * @param label The height of the label line above the grid.
+ * @return The top-left corner of the grid and its cell size.
+ */
+fn slot_grid(
+    (left, top, width, height): (f64, f64, f64, f64),
+    (cols, rows): (usize, usize),
+    slot: usize,
+    size: usize,
+    label: f64,
+) -> (f64, f64, f64) {
+    let (slot_width, slot_height) = (width / cols as f64, height / rows as f64);
+    let gap = LABEL.max(label * 1.5);
+    let side = (slot_width - gap).min(slot_height - gap - label);
+    let cell = side / (size as f64 + 1.0 / 16.0);
+    let (col, row) = (slot % cols, slot / cols);
+    let x = left + col as f64 * slot_width + (slot_width - side) / 2.0 + border_width(cell) / 2.0;
+    let y = top + row as f64 * slot_height + label + border_width(cell) / 2.0;
+    (x, y, cell)
+}
@@ -0,0 +1,248 @@
+use std::io::{self, BufRead};
+use std::num::NonZeroUsize;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::format::{self, ParseError};
+use crate::grid::Grid;
+use crate::solver::Solver;
+
+/** Lines each worker thread solves per batch. */
+const LINES_PER_THREAD: usize = 256;
+
+/**
+ * What became of one line of a bulk solve.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /**
+     * The puzzle was solved: the first solution found and how many there
+     * are, up to the solver's limit.
+     */
+    Solved { solution: Grid, solutions: usize },
+    /** The puzzle has no solution. */
+    Unsolvable,
+    /** The line is not a puzzle. */
+    Malformed(ParseError),
+}
+
+/**
+ * The result of one input line.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineReport {
+    /** The line number, counting from 1. */
+    pub line: usize,
+    pub outcome: Outcome,
+    /** Time spent solving the line. */
+    pub elapsed: Duration,
+}
+
+impl LineReport {
+    /**
+     * Returns true if the line held a puzzle with exactly one solution.
+     */
+    pub fn is_success(&self) -> bool {
+        matches!(self.outcome, Outcome::Solved { solutions: 1, .. })
+    }
+}
+
+/**
+ * Totals over every line of a bulk solve.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    /** Lines that held a puzzle, well formed or not. */
+    pub puzzles: usize,
+    /** Puzzles with exactly one solution. */
+    pub unique: usize,
+    /** Puzzles with more than one solution. */
+    pub multiple: usize,
+    /** Puzzles with no solution. */
+    pub unsolvable: usize,
+    /** Lines that did not parse. */
+    pub malformed: usize,
+    /** Wall-clock time of the whole run. */
+    pub elapsed: Duration,
+    /** Time spent solving, summed over every line and thread. */
+    pub solving: Duration,
+    /** Worker threads used. */
+    pub threads: usize,
+}
+
+impl Summary {
+    fn add(&mut self, report: &LineReport) {
+        self.puzzles += 1;
+        self.solving += report.elapsed;
+        match report.outcome {
+            Outcome::Solved { solutions: 1, .. } => self.unique += 1,
+            Outcome::Solved { .. } => self.multiple += 1,
+            Outcome::Unsolvable => self.unsolvable += 1,
+            Outcome::Malformed(_) => self.malformed += 1,
+        }
+    }
+
+    /**
+     * Returns the number of lines that were not a puzzle with exactly one
+     * solution.
+     */
+    pub fn failures(&self) -> usize {
+        self.puzzles - self.unique
+    }
+
+    /**
+     * Returns the puzzles handled per second of wall-clock time.
+     */
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.puzzles as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+/**
+ * Solves a stream of puzzles, one per line, across several threads.
+ *
+ * Every line is solved and its solutions counted in one search. Reports come
+ * back in input order; blank lines and lines starting with `#` are skipped
+ * but still counted for line numbers.
+ */
+#[derive(Debug, Clone)]
+pub struct BulkSolver {
+    threads: usize,
+    limit: usize,
+}
+
+impl Default for BulkSolver {
+    fn default() -> Self {
+        BulkSolver::new()
+    }
+}
+
+impl BulkSolver {
+    /**
+     * Creates a bulk solver using every available core and counting up to
+     * two solutions, which is enough to tell unique puzzles apart.
+     */
+    pub fn new() -> Self {
+        BulkSolver {
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            limit: 2,
+        }
+    }
+
+    /**
+     * Sets the number of worker threads (at least 1).
+     */
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /**
+     * Sets the solution count at which to stop searching (at least 2, so
+     * that unique puzzles stand apart).
+     */
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit.max(2);
+        self
+    }
+
+    /**
+     * Solves every puzzle of the input.
+     *
+     * Lines are read in batches, so memory stays bounded however long the
+     * input is; each batch is split across the worker threads and its
+     * reports are passed to `report` in order before the next is read.
+     *
+     * @param input The puzzles, one per line.
+     * @param report Called with the result of every puzzle line.
+     * @return The totals, or the error that stopped reading the input.
+     */
+    pub fn run(
+        &self,
+        input: impl BufRead,
+        mut report: impl FnMut(&LineReport),
+    ) -> io::Result<Summary> {
+        let start = Instant::now();
+        let mut summary = Summary {
+            threads: self.threads,
+            ..Summary::default()
+        };
+        let batch_size = self.threads * LINES_PER_THREAD;
+        let mut lines = input.lines().enumerate();
+        let mut batch = Vec::with_capacity(batch_size);
+        loop {
+            batch.clear();
+            for (number, line) in lines.by_ref() {
+                let line = line?;
+                let trimmed = line.trim();
+                if trimmed.is_empty() || trimmed.starts_with('#') {
+                    continue;
+                }
+                batch.push((number + 1, line));
+                if batch.len() == batch_size {
+                    break;
+                }
+            }
+            if batch.is_empty() {
+                break;
+            }
+            for line_report in self.solve_batch(&batch) {
+                summary.add(&line_report);
+                report(&line_report);
+            }
+        }
+        summary.elapsed = start.elapsed();
+        Ok(summary)
+    }
+
+    /**
+     * Solves a batch of numbered lines, one contiguous chunk per thread.
+     */
+    fn solve_batch(&self, batch: &[(usize, String)]) -> Vec<LineReport> {
+        let chunk_size = batch.len().div_ceil(self.threads);
+        thread::scope(|scope| {
+            let workers: Vec<_> = batch
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(number, line)| self.solve_line(*number, line))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("solver thread panicked"))
+                .collect()
+        })
+    }
+
+    fn solve_line(&self, number: usize, line: &str) -> LineReport {
+        let start = Instant::now();
+        let outcome = match format::parse(line) {
+            Ok(grid) => match Solver::new().solve_count(&grid, self.limit) {
+                (Some(solution), solutions) => Outcome::Solved {
+                    solution,
+                    solutions,
+                },
+                (None, _) => Outcome::Unsolvable,
+            },
+            Err(error) => Outcome::Malformed(ParseError {
+                line: number,
+                ..error
+            }),
+        };
+        LineReport {
+            line: number,
+            outcome,
+            elapsed: start.elapsed(),
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::str::FromStr;
+
+/**
+ * A command-line flag: `--name`, optionally with a one-letter alias and a
+ * value.
+ */
+pub struct Flag {
+    pub name: &'static str,
+    pub short: Option<char>,
+    /** Placeholder shown in the help for the value, `None` for a switch. */
+    pub value: Option<&'static str>,
+    pub help: &'static str,
+}
+
+/** The `--help` switch every command accepts. */
+pub const HELP: Flag = Flag {
+    name: "help",
+    short: Some('h'),
+    value: None,
+    help: "Print this help",
+};
+
+/**
+ * The flags and positional arguments of one command line.
+ */
+#[derive(Debug, Default)]
+pub struct Matches {
+    values: HashMap<&'static str, String>,
+    pub positional: Vec<String>,
+}
+
+impl Matches {
+    /**
+     * Returns true if a switch was given.
+     */
+    pub fn is_set(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /**
+     * Parses the value of a flag.
+     * @return The value, `None` if the flag was not given, or a message
+     * naming the flag if its value does not parse.
+     */
+    pub fn value<T>(&self, name: &str) -> Result<Option<T>, String>
+    where
+        T: FromStr,
+        T::Err: ToString,
+    {
+        self.values
+            .get(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|error: T::Err| format!("--{}: {}", name, error.to_string()))
+            })
+            .transpose()
+    }
+
+    /**
+     * Parses the value of a count flag that has a lower bound.
+     * @return The value, `None` if the flag was not given, or a message
+     * naming the flag if its value does not parse or is below `min`.
+     */
+    pub fn value_at_least(&self, name: &str, min: usize) -> Result<Option<usize>, String> {
+        match self.value(name)? {
+            Some(value) if value < min => Err(format!("--{}: must be at least {}", name, min)),
+            value => Ok(value),
+        }
+    }
+}
+
+/**
+ * Matches command-line arguments against a command's flags.
+ *
+ * Values may follow their flag as the next argument or after `=`, and `--`
+ * ends flag parsing. A lone `-` is positional.
+ *
+ * @param flags The flags the command accepts.
+ * @param args The arguments after the command name.
+ * @return The matches, or a message describing the first bad argument.
+ */
+pub fn parse(flags: &[Flag], args: &[String]) -> Result<Matches, String> {
+    let mut matches = Matches::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            matches.positional.extend(args.cloned());
+            break;
+        }
+        let (key, inline) = match arg.strip_prefix("--") {
+            Some(rest) => match rest.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (rest, None),
+            },
+            None if arg.len() > 1 && arg.starts_with('-') => (&arg[1..], None),
+            None => {
+                matches.positional.push(arg.clone());
+                continue;
+            }
+        };
+        let long = arg.starts_with("--");
+        let flag = flags
+            .iter()
+            .find(|flag| {
+                if long {
+                    flag.name == key
+                } else {
+                    flag.short.is_some_and(|short| key == short.to_string())
+                }
+            })
+            .ok_or_else(|| format!("unknown option '{}'", arg))?;
+        let value = match (flag.value, inline) {
+            (None, None) => String::new(),
+            (None, Some(_)) => return Err(format!("--{} does not take a value", flag.name)),
+            (Some(_), Some(value)) => value,
+            (Some(_), None) => args
+                .next()
+                .cloned()
+                .ok_or_else(|| format!("--{} needs a value", flag.name))?,
+        };
+        matches.values.insert(flag.name, value);
+    }
+    Ok(matches)
+}
+
+/**
+ * Formats the help of a command.
+ * @param usage The usage line, without the program name.
+ * @param about What the command does.
+ * @param flags The flags the command accepts.
+ */
+pub fn help(usage: &str, about: &str, flags: &[Flag]) -> String {
+    let mut text = format!("{}\n\nusage: sudoku {}\n\noptions:\n", about, usage);
+    let names: Vec<String> = flags
+        .iter()
+        .map(|flag| {
+            let short = flag
+                .short
+                .map_or("    ".to_string(), |c| format!("-{}, ", c));
+            let value = flag.value.map_or(String::new(), |v| format!(" <{}>", v));
+            format!("{}--{}{}", short, flag.name, value)
+        })
+        .collect();
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+    for (name, flag) in names.iter().zip(flags) {
+        writeln!(text, "  {:width$}  {}", name, flag.help, width = width).unwrap();
+    }
+    text
+}
@@ -0,0 +1,1247 @@
+/*!
+ * The `sudoku` command line: subcommands, their flags and their output.
+ */
+
+mod args;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
+use std::str::FromStr;
+
+use rand::Rng;
+use sudoku::{
+    Booklet, BookletOptions, BulkSolver, Cages, Candidates, Format, Generator, Grid, KillerOptions,
+    KillerPuzzle, LogicalSolver, Outcome, PuzzleOptions, Regions, Renderer, Rules, Shape, Solver,
+    Style, SvgRenderer, Symmetry, Technique, Tier, CELLS,
+};
+
+use args::{Flag, Matches, HELP};
+
+/** Every puzzle was handled successfully. */
+const EXIT_OK: i32 = 0;
+/** Some puzzle was invalid, unsolvable or could not be generated. */
+const EXIT_FAILURE: i32 = 1;
+/** The command line was malformed. */
+const EXIT_USAGE: i32 = 2;
+/** Input could not be read. */
+const EXIT_IO: i32 = 3;
+
+/**
+ * The largest grid `generate` makes random jigsaw layouts for; filling
+ * larger ones can take minutes.
+ */
+const MAX_JIGSAW_SIZE: usize = 12;
+
+/**
+ * Why a command stopped before handling every puzzle.
+ */
+enum Error {
+    Usage(String),
+    Io(String, io::Error),
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Self {
+        Error::Usage(message)
+    }
+}
+
+type CommandResult = Result<i32, Error>;
+
+struct Command {
+    name: &'static str,
+    usage: &'static str,
+    about: &'static str,
+    flags: &'static [Flag],
+    run: fn(&Matches) -> CommandResult,
+}
+
+const INPUT: Flag = Flag {
+    name: "input",
+    short: Some('i'),
+    value: Some("file"),
+    help: "Read puzzles from a file ('-' for stdin)",
+};
+
+const FORMAT: Flag = Flag {
+    name: "format",
+    short: Some('f'),
+    value: Some("format"),
+    help: "Output format: line, sdk, ss, grid, unicode, ascii or svg [default: line]",
+};
+
+const BLANK: Flag = Flag {
+    name: "blank",
+    short: None,
+    value: Some("char"),
+    help: "Blank cells in unicode and ascii output: dot or space [default: dot]",
+};
+
+const COLOR: Flag = Flag {
+    name: "color",
+    short: None,
+    value: Some("when"),
+    help: "Color unicode and ascii output: auto, always or never [default: auto]",
+};
+
+const CELL_SIZE: Flag = Flag {
+    name: "cell-size",
+    short: None,
+    value: Some("units"),
+    help: "Side of a cell in svg output [default: 40]",
+};
+
+const FONT: Flag = Flag {
+    name: "font",
+    short: None,
+    value: Some("family"),
+    help: "Font family of svg output [default: sans-serif]",
+};
+
+const PENCIL_MARKS: Flag = Flag {
+    name: "pencil-marks",
+    short: None,
+    value: None,
+    help: "Show the candidates of blank cells in svg output",
+};
+
+const BOX: Flag = Flag {
+    name: "box",
+    short: Some('b'),
+    value: Some("RxC"),
+    help: "Box height and width, e.g. 2x3 for 6x6 or 4x4 for 16x16 [default: 3x3]",
+};
+
+const INPUT_BOX: Flag = Flag {
+    name: "box",
+    short: Some('b'),
+    value: Some("RxC"),
+    help: "Box height and width of the puzzles [default: guessed from the text]",
+};
+
+const VARIANT: Flag = Flag {
+    name: "variant",
+    short: None,
+    value: Some("rules"),
+    help: "classic, diagonal for Sudoku-X with shaded diagonals, jigsaw with --regions, \
+           or killer with --cages [default: classic]",
+};
+
+const REGIONS: Flag = Flag {
+    name: "regions",
+    short: None,
+    value: Some("map"),
+    help: "Region of each cell of jigsaw puzzles, one digit or letter per cell in row-major order",
+};
+
+const CAGES: Flag = Flag {
+    name: "cages",
+    short: None,
+    value: Some("list"),
+    help: "Cages of killer puzzles as generate lists them, 'sum: r1c1 r1c2' on each line \
+           or separated by ';'; outlined in svg output",
+};
+
+const COMMANDS: &[Command] = &[
+    Command {
+        name: "generate",
+        usage: "generate [options]",
+        about: "Generate puzzles with a unique solution.",
+        flags: &[
+            Flag {
+                name: "count",
+                short: Some('n'),
+                value: Some("n"),
+                help: "Number of puzzles [default: 1]",
+            },
+            Flag {
+                name: "clues",
+                short: Some('c'),
+                value: Some("n"),
+                help: "Exact clue count; an upper bound with --difficulty, \
+                       --technique or --minimal [default: 40 for 9x9, or every cell \
+                       with those, or none for killer puzzles]",
+            },
+            BOX,
+            Flag {
+                name: "variant",
+                short: None,
+                value: Some("rules"),
+                help: "classic, diagonal for Sudoku-X, jigsaw for irregular regions, or \
+                       killer for cages with sums, outlined in svg output and listed after \
+                       the givens otherwise [default: classic]",
+            },
+            Flag {
+                name: "regions",
+                short: None,
+                value: Some("map"),
+                help: "Region of each cell of jigsaw puzzles, one digit or letter per cell \
+                       in row-major order [default: random for each puzzle]",
+            },
+            Flag {
+                name: "cage-size",
+                short: None,
+                value: Some("n"),
+                help: "Largest cage of killer puzzles [default: 5]",
+            },
+            Flag {
+                name: "symmetry",
+                short: Some('s'),
+                value: Some("kind"),
+                help: "none, rotational, rotational90, horizontal, vertical, \
+                       diagonal or dihedral [default: none]",
+            },
+            Flag {
+                name: "difficulty",
+                short: Some('d'),
+                value: Some("tier"),
+                help: "easy, medium, hard, expert or diabolical; classic puzzles only",
+            },
+            Flag {
+                name: "technique",
+                short: Some('t'),
+                value: Some("name"),
+                help: "Technique the solve path must need, e.g. x-wing",
+            },
+            Flag {
+                name: "max-technique",
+                short: None,
+                value: Some("name"),
+                help: "Hardest technique the solve path may use",
+            },
+            Flag {
+                name: "minimal",
+                short: Some('m'),
+                value: None,
+                help: "Only keep clues that are needed for uniqueness",
+            },
+            Flag {
+                name: "attempts",
+                short: None,
+                value: Some("n"),
+                help: "Fresh grids to try per puzzle [default: 100]",
+            },
+            Flag {
+                name: "seed",
+                short: None,
+                value: Some("u64"),
+                help: "Seed for reproducible output [default: random]",
+            },
+            FORMAT,
+            BLANK,
+            COLOR,
+            CELL_SIZE,
+            FONT,
+            PENCIL_MARKS,
+            HELP,
+        ],
+        run: generate,
+    },
+    Command {
+        name: "booklet",
+        usage: "booklet [options] --output <file>",
+        about: "Lay out generated puzzles and their answers as a printable PDF.",
+        flags: &[
+            Flag {
+                name: "output",
+                short: Some('o'),
+                value: Some("file"),
+                help: "PDF file to write ('-' for stdout)",
+            },
+            Flag {
+                name: "count",
+                short: Some('n'),
+                value: Some("n"),
+                help: "Number of puzzles [default: 12]",
+            },
+            BOX,
+            Flag {
+                name: "difficulty",
+                short: Some('d'),
+                value: Some("tiers"),
+                help: "Comma-separated tiers to spread the puzzles over \
+                       [default: easy,medium,hard,expert]",
+            },
+            Flag {
+                name: "per-page",
+                short: Some('p'),
+                value: Some("n"),
+                help: "Puzzles per page [default: 4]",
+            },
+            Flag {
+                name: "page-size",
+                short: None,
+                value: Some("size"),
+                help: "a4 or letter [default: a4]",
+            },
+            Flag {
+                name: "title",
+                short: None,
+                value: Some("text"),
+                help: "Title printed on every page [default: Sudoku]",
+            },
+            Flag {
+                name: "attempts",
+                short: None,
+                value: Some("n"),
+                help: "Fresh grids to try per puzzle [default: 1000]",
+            },
+            Flag {
+                name: "seed",
+                short: None,
+                value: Some("u64"),
+                help: "Seed for reproducible output [default: random]",
+            },
+            HELP,
+        ],
+        run: booklet,
+    },
+    Command {
+        name: "solve",
+        usage: "solve [options] [puzzle...]",
+        about: "Print the solution of each puzzle.",
+        flags: &[
+            INPUT,
+            INPUT_BOX,
+            VARIANT,
+            REGIONS,
+            CAGES,
+            Flag {
+                name: "max",
+                short: Some('m'),
+                value: Some("n"),
+                help: "Print up to n solutions of puzzles that have several [default: 1]",
+            },
+            FORMAT,
+            BLANK,
+            COLOR,
+            CELL_SIZE,
+            FONT,
+            PENCIL_MARKS,
+            HELP,
+        ],
+        run: solve,
+    },
+    Command {
+        name: "bulk",
+        usage: "bulk [options] [file]",
+        about: "Solve a file of puzzles, one per line, on every core.",
+        flags: &[
+            INPUT,
+            Flag {
+                name: "threads",
+                short: Some('j'),
+                value: Some("n"),
+                help: "Worker threads [default: number of cores]",
+            },
+            Flag {
+                name: "limit",
+                short: Some('l'),
+                value: Some("n"),
+                help: "Stop counting at this many solutions, at least 2 [default: 2]",
+            },
+            Flag {
+                name: "quiet",
+                short: Some('q'),
+                value: None,
+                help: "Only print the summary",
+            },
+            HELP,
+        ],
+        run: bulk,
+    },
+    Command {
+        name: "count",
+        usage: "count [options] [puzzle...]",
+        about: "Count the solutions of each puzzle.",
+        flags: &[
+            INPUT,
+            INPUT_BOX,
+            VARIANT,
+            REGIONS,
+            CAGES,
+            Flag {
+                name: "limit",
+                short: Some('l'),
+                value: Some("n"),
+                help: "Stop counting at this many solutions, at least 2 [default: 1000]",
+            },
+            HELP,
+        ],
+        run: count,
+    },
+    Command {
+        name: "rate",
+        usage: "rate [options] [puzzle...]",
+        about: "Print the difficulty tier, score and hardest technique of each puzzle.",
+        flags: &[
+            INPUT,
+            INPUT_BOX,
+            Flag {
+                name: "verbose",
+                short: Some('v'),
+                value: None,
+                help: "Also print how often each technique was used",
+            },
+            HELP,
+        ],
+        run: rate,
+    },
+    Command {
+        name: "validate",
+        usage: "validate [options] [puzzle...]",
+        about: "Check that each puzzle is well formed and has exactly one solution.",
+        flags: &[INPUT, INPUT_BOX, VARIANT, REGIONS, CAGES, HELP],
+        run: validate,
+    },
+    Command {
+        name: "hint",
+        usage: "hint [options] [puzzle...]",
+        about: "Print the next logical deduction for each puzzle.",
+        flags: &[
+            INPUT,
+            INPUT_BOX,
+            Flag {
+                name: "all",
+                short: Some('a'),
+                value: None,
+                help: "Print every step of the logical solve",
+            },
+            HELP,
+        ],
+        run: hint,
+    },
+    Command {
+        name: "convert",
+        usage: "convert [options] [puzzle...]",
+        about: "Rewrite each puzzle in another format.",
+        flags: &[
+            INPUT,
+            INPUT_BOX,
+            VARIANT,
+            REGIONS,
+            CAGES,
+            FORMAT,
+            BLANK,
+            COLOR,
+            CELL_SIZE,
+            FONT,
+            PENCIL_MARKS,
+            HELP,
+        ],
+        run: convert,
+    },
+];
+
+/**
+ * Runs the command line.
+ * @param args The arguments without the program name.
+ * @return The process exit code.
+ */
+pub fn run(args: &[String]) -> i32 {
+    let Some(name) = args.first() else {
+        eprint!("{}", overview());
+        return EXIT_USAGE;
+    };
+    if name == "--help" || name == "-h" || name == "help" {
+        print!("{}", overview());
+        return EXIT_OK;
+    }
+    let Some(command) = COMMANDS.iter().find(|command| command.name == name) else {
+        eprintln!("error: unknown command '{}'", name);
+        eprint!("{}", overview());
+        return EXIT_USAGE;
+    };
+    let result = args::parse(command.flags, &args[1..]).map_err(Error::Usage);
+    let result = result.and_then(|matches| {
+        if matches.is_set("help") {
+            print!(
+                "{}",
+                args::help(command.usage, command.about, command.flags)
+            );
+            Ok(EXIT_OK)
+        } else {
+            (command.run)(&matches)
+        }
+    });
+    match result {
+        Ok(code) => code,
+        Err(Error::Usage(message)) => {
+            eprintln!("error: {}", message);
+            eprintln!("try 'sudoku {} --help'", command.name);
+            EXIT_USAGE
+        }
+        Err(Error::Io(path, error)) => {
+            eprintln!("error: {}: {}", path, error);
+            EXIT_IO
+        }
+    }
+}
+
+fn overview() -> String {
+    let mut text = String::from("Generate, solve and rate Sudoku puzzles.\n\n");
+    text.push_str("usage: sudoku <command> [options]\n\ncommands:\n");
+    for command in COMMANDS {
+        text.push_str(&format!("  {:10}{}\n", command.name, command.about));
+    }
+    text.push_str(
+        "\nPuzzles are 81 characters with 0 or . for blanks, or .sdk or .ss grids. \
+         Commands that\ntake puzzles read them from the arguments, from --input, \
+         or else from stdin.\n\
+         Killer puzzles take their cages from --cages, as 'generate --variant killer' \
+         lists them.\n\
+         Run 'sudoku <command> --help' for the options of a command.\n\n\
+         exit codes: 0 success, 1 a puzzle failed, 2 usage error, 3 input error\n",
+    );
+    text
+}
+
+/**
+ * How grids are written.
+ */
+#[derive(Clone, Copy)]
+enum Output {
+    /** One of the library's file formats. */
+    Text(Format),
+    /** One line per row of space-separated digits, `0` for blanks. */
+    Digits,
+    /** A framed drawing for the terminal. */
+    Drawing(Style),
+    /** An SVG document. */
+    Svg,
+}
+
+impl FromStr for Output {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "grid" => return Ok(Output::Digits),
+            "svg" => return Ok(Output::Svg),
+            _ => {}
+        }
+        s.parse()
+            .map(Output::Text)
+            .or_else(|_| s.parse().map(Output::Drawing))
+            .map_err(|_| format!("unknown format '{}'", s))
+    }
+}
+
+/**
+ * The value of `--blank`: a name or a single character.
+ */
+struct Blank(char);
+
+impl FromStr for Blank {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (s, chars.next(), chars.next()) {
+            ("dot", ..) => Ok(Blank('.')),
+            ("space", ..) => Ok(Blank(' ')),
+            (_, Some(c), None) => Ok(Blank(c)),
+            _ => Err(format!(
+                "expected dot, space or one character, found '{}'",
+                s
+            )),
+        }
+    }
+}
+
+/**
+ * The value of `--color`.
+ */
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!("expected auto, always or never, found '{}'", s)),
+        }
+    }
+}
+
+/**
+ * The value of `--variant`: the rules puzzles follow.
+ */
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Variant {
+    Classic,
+    Diagonal,
+    /** Irregular regions, read from `--regions` or made up by `generate`. */
+    Jigsaw,
+    /** Cages with sums, read from `--cages` or made up by `generate`. */
+    Killer,
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "classic" => Ok(Variant::Classic),
+            "diagonal" | "x" => Ok(Variant::Diagonal),
+            "jigsaw" => Ok(Variant::Jigsaw),
+            "killer" => Ok(Variant::Killer),
+            _ => Err(format!(
+                "expected classic, diagonal, jigsaw or killer, found '{}'",
+                s
+            )),
+        }
+    }
+}
+
+impl Variant {
+    /**
+     * Reads `--variant`, classic when it is not given, and checks that
+     * `--regions` only comes with jigsaw puzzles and `--cages` with killer
+     * puzzles.
+     */
+    fn from_matches(matches: &Matches) -> Result<Self, Error> {
+        let variant = matches.value("variant")?.unwrap_or(Variant::Classic);
+        if matches.is_set("regions") && variant != Variant::Jigsaw {
+            return Err(Error::Usage(
+                "--regions only applies to jigsaw puzzles".to_string(),
+            ));
+        }
+        if matches.is_set("cages") && variant != Variant::Killer {
+            return Err(Error::Usage(
+                "--cages only applies to killer puzzles".to_string(),
+            ));
+        }
+        Ok(variant)
+    }
+
+    /**
+     * Returns the rules of puzzles that are read or generated from clues,
+     * taking jigsaw regions from `--regions` and killer cages from `--cages`.
+     */
+    fn rules(self, matches: &Matches) -> Result<Rules, Error> {
+        match self {
+            Variant::Classic => Ok(Rules::classic()),
+            Variant::Diagonal => Ok(Rules::diagonal()),
+            Variant::Jigsaw => match matches.value("regions")? {
+                Some(regions) => Ok(Rules::jigsaw(regions)),
+                None => Err(Error::Usage("jigsaw puzzles need --regions".to_string())),
+            },
+            Variant::Killer => match matches.value::<Cages>("cages")? {
+                Some(cages) => Ok(Rules::classic().with(cages)),
+                None => Err(Error::Usage("killer puzzles need --cages".to_string())),
+            },
+        }
+    }
+}
+
+/**
+ * Writes grids in a format, separating multi-line grids by a blank line.
+ */
+struct Printer {
+    output: Output,
+    renderer: Renderer,
+    svg: SvgRenderer,
+    printed: bool,
+}
+
+impl Printer {
+    /**
+     * Reads `--format` and, where the command takes them, the options of
+     * drawings and SVG output. Automatic color is on only when stdout is a terminal and
+     * `NO_COLOR` is not set.
+     */
+    fn new(matches: &Matches) -> Result<Self, Error> {
+        let output = matches
+            .value("format")?
+            .unwrap_or(Output::Text(Format::Line));
+        let color = match matches.value("color")?.unwrap_or(ColorChoice::Auto) {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none(),
+        };
+        let diagonals = Variant::from_matches(matches)? == Variant::Diagonal;
+        let mut renderer = Renderer::new().color(color).diagonals(diagonals);
+        if let Output::Drawing(style) = output {
+            renderer = renderer.style(style);
+        }
+        if let Some(Blank(blank)) = matches.value("blank")? {
+            renderer = renderer.blank(blank);
+        }
+        let mut svg = SvgRenderer::new()
+            .pencil_marks(matches.is_set("pencil-marks"))
+            .diagonals(diagonals);
+        if let Some(regions) = matches.value::<Regions>("regions")? {
+            renderer = renderer.regions(&regions);
+            svg = svg.regions(&regions);
+        }
+        if let Some(cages) = matches.value::<Cages>("cages")? {
+            svg = svg.cages(&cages);
+        }
+        if let Some(cell_size) = matches.value("cell-size")? {
+            svg = svg.cell_size(cell_size);
+        }
+        if let Some(font) = matches.value::<String>("font")? {
+            svg = svg.font_family(&font);
+        }
+        Ok(Printer {
+            output,
+            renderer,
+            svg,
+            printed: false,
+        })
+    }
+
+    fn print(&mut self, grid: &Grid) {
+        self.print_solved(grid, grid);
+    }
+
+    /**
+     * Prints a grid filled in from `givens`; drawings tell the two apart.
+     */
+    fn print_solved(&mut self, givens: &Grid, grid: &Grid) {
+        self.separate(!matches!(self.output, Output::Text(Format::Line)));
+        print!("{}", self.render(givens, grid));
+    }
+
+    /**
+     * Prints a Killer Sudoku. SVG output outlines the cages; other formats
+     * list them after the givens, one per line, so puzzles are always
+     * separated by a blank line.
+     */
+    fn print_killer(&mut self, puzzle: &KillerPuzzle) {
+        self.separate(true);
+        let givens = puzzle.givens();
+        match self.output {
+            Output::Svg => print!("{}", self.svg.clone().cages(puzzle.cages()).render(givens)),
+            _ => print!("{}{}", self.render(givens, givens), puzzle.cages()),
+        }
+    }
+
+    /**
+     * Prints a Jigsaw Sudoku. Drawings show the regions; other formats
+     * write the region map after the givens, so puzzles are always separated
+     * by a blank line.
+     */
+    fn print_jigsaw(&mut self, givens: &Grid, regions: &Regions) {
+        self.separate(true);
+        match self.output {
+            Output::Svg => print!("{}", self.svg.clone().regions(regions).render(givens)),
+            Output::Drawing(_) => {
+                print!("{}", self.renderer.clone().regions(regions).render(givens))
+            }
+            _ => print!("{}{}", self.render(givens, givens), regions),
+        }
+    }
+
+    /**
+     * Starts a new grid, after a blank line if there was an earlier one and
+     * the format spans several lines.
+     */
+    fn separate(&mut self, multi_line: bool) {
+        if self.printed && multi_line {
+            println!();
+        }
+        self.printed = true;
+    }
+
+    fn render(&self, givens: &Grid, grid: &Grid) -> String {
+        match self.output {
+            Output::Text(format) => format.write(grid),
+            Output::Digits => grid.to_string(),
+            Output::Drawing(_) => self.renderer.render_solved(givens, grid),
+            Output::Svg => self.svg.render_solved(givens, grid),
+        }
+    }
+}
+
+/**
+ * A puzzle as it was read, with where it came from.
+ */
+struct Input {
+    source: String,
+    grid: Result<Grid, String>,
+}
+
+/**
+ * Reads the shape of the grids from `--box`, or else from `--regions` or
+ * `--cages`, and checks that the regions and cages fit it.
+ * @return The shape, or `None` to take it from the text of each puzzle.
+ */
+fn grid_shape(matches: &Matches) -> Result<Option<Shape>, Error> {
+    let regions: Option<Regions> = matches.value("regions")?;
+    let cages: Option<Cages> = matches.value("cages")?;
+    let Some(shape) = matches.value::<Shape>("box")? else {
+        return Ok(regions
+            .map(|regions| regions.shape())
+            .or(cages.map(|cages| cages.shape())));
+    };
+    if let Some(regions) = regions.filter(|regions| regions.shape().size() != shape.size()) {
+        return Err(Error::Usage(format!(
+            "--regions has {} cells, but the grid has {}",
+            regions.shape().cells(),
+            shape.cells()
+        )));
+    }
+    if let Some(cages) = cages.filter(|cages| cages.shape().size() != shape.size()) {
+        return Err(Error::Usage(format!(
+            "--cages are for a grid of {} cells, but the grid has {}",
+            cages.shape().cells(),
+            shape.cells()
+        )));
+    }
+    Ok(Some(shape))
+}
+
+/**
+ * Collects the puzzles of a command: each positional argument, or else the
+ * puzzles in `--input` or stdin. Files may hold single-line puzzles and
+ * multi-line `.sdk` or `.ss` grids; lines starting with `#` are skipped.
+ */
+fn read_inputs(matches: &Matches) -> Result<Vec<Input>, Error> {
+    let shape = grid_shape(matches)?;
+    if !matches.positional.is_empty() {
+        return Ok(matches
+            .positional
+            .iter()
+            .map(|text| {
+                let grid = match shape {
+                    Some(shape) => sudoku::parse_shaped(text, shape),
+                    None => sudoku::parse(text),
+                };
+                Input {
+                    source: text.clone(),
+                    grid: grid.map_err(|error| error.to_string()),
+                }
+            })
+            .collect());
+    }
+    let (source, text) = match matches.value::<String>("input")? {
+        Some(path) if path != "-" => {
+            let text = fs::read_to_string(&path).map_err(|error| Error::Io(path.clone(), error))?;
+            (path, text)
+        }
+        _ => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|error| Error::Io("stdin".to_string(), error))?;
+            ("stdin".to_string(), text)
+        }
+    };
+    let grids = match shape {
+        Some(shape) => sudoku::parse_many_shaped(&text, shape),
+        None => sudoku::parse_many(&text),
+    };
+    Ok(grids
+        .into_iter()
+        .map(|grid| Input {
+            source: source.clone(),
+            grid: grid.map_err(|error| error.to_string()),
+        })
+        .collect())
+}
+
+/**
+ * Runs `action` on every puzzle that parses and reports the others.
+ * @return `EXIT_FAILURE` if any puzzle failed to parse or `action` returned
+ * false for it, `EXIT_OK` otherwise.
+ */
+fn for_each_puzzle(matches: &Matches, mut action: impl FnMut(&Grid) -> bool) -> CommandResult {
+    let mut code = EXIT_OK;
+    for input in read_inputs(matches)? {
+        match input.grid {
+            Ok(grid) => {
+                if !action(&grid) {
+                    code = EXIT_FAILURE;
+                }
+            }
+            Err(message) => {
+                eprintln!("error: {}: {}", input.source, message);
+                code = EXIT_FAILURE;
+            }
+        }
+    }
+    Ok(code)
+}
+
+fn generate(matches: &Matches) -> CommandResult {
+    let count = matches.value("count")?.unwrap_or(1);
+    let seed = matches
+        .value("seed")?
+        .unwrap_or_else(|| rand::thread_rng().gen());
+    let difficulty: Option<Tier> = matches.value("difficulty")?;
+    let technique: Option<Technique> = matches.value("technique")?;
+    let max_technique: Option<Technique> = matches.value("max-technique")?;
+    let minimal = matches.is_set("minimal");
+    let bounded = minimal || difficulty.is_some() || technique.is_some();
+    let variant = Variant::from_matches(matches)?;
+    let regions: Option<Regions> = matches.value("regions")?;
+    let shape = grid_shape(matches)?.unwrap_or_default();
+    if variant == Variant::Killer {
+        return generate_killer(matches, count, seed, shape);
+    }
+    // Ratings only know deductions from rows, columns and boxes.
+    if variant != Variant::Classic {
+        for flag in ["difficulty", "technique", "max-technique"] {
+            if matches.is_set(flag) {
+                return Err(Error::Usage(format!(
+                    "--{} only applies to classic puzzles",
+                    flag
+                )));
+            }
+        }
+    }
+    if variant == Variant::Jigsaw && regions.is_none() && shape.size() > MAX_JIGSAW_SIZE {
+        return Err(Error::Usage(format!(
+            "random jigsaw layouts go up to {0}x{0}; pass --regions for larger grids",
+            MAX_JIGSAW_SIZE
+        )));
+    }
+    let clues = matches.value("clues")?.unwrap_or(if bounded {
+        shape.cells()
+    } else {
+        shape.cells() * 40 / CELLS
+    });
+
+    let mut options = PuzzleOptions::new(clues)
+        .shape(shape)
+        .symmetry(matches.value("symmetry")?.unwrap_or(Symmetry::None))
+        .minimal(minimal);
+    if variant != Variant::Jigsaw || regions.is_some() {
+        options = options.rules(variant.rules(matches)?);
+    }
+    if let Some(attempts) = matches.value("attempts")? {
+        options = options.max_attempts(attempts);
+    }
+    if let Some(tier) = difficulty {
+        options = options.difficulty(tier);
+    }
+    if let Some(technique) = technique {
+        options = options.technique(technique);
+    }
+    if let Some(technique) = max_technique {
+        options = options.max_technique(technique);
+    }
+
+    let mut printer = Printer::new(matches)?;
+    eprintln!("seed: {}", seed);
+    let mut generator = Generator::from_seed(seed);
+    for _ in 0..count {
+        // Jigsaw puzzles without --regions each get a layout of their own.
+        let jigsaw = match (variant, &regions) {
+            (Variant::Jigsaw, Some(regions)) => Some(regions.clone()),
+            (Variant::Jigsaw, None) => {
+                let regions = generator.regions(shape);
+                options = options.rules(Rules::jigsaw(regions.clone()));
+                Some(regions)
+            }
+            _ => None,
+        };
+        match generator.generate(&options) {
+            Ok(puzzle) => match &jigsaw {
+                Some(regions) => printer.print_jigsaw(puzzle.givens(), regions),
+                None => printer.print(puzzle.givens()),
+            },
+            Err(error) => {
+                eprintln!("error: {}", error);
+                return Ok(EXIT_FAILURE);
+            }
+        }
+    }
+    Ok(EXIT_OK)
+}
+
+/**
+ * Generates Killer Sudoku for `generate --variant killer`. `--clues` counts
+ * the digits given besides the cages, and the options of clue removal do not
+ * apply.
+ */
+fn generate_killer(matches: &Matches, count: usize, seed: u64, shape: Shape) -> CommandResult {
+    for flag in [
+        "symmetry",
+        "difficulty",
+        "technique",
+        "max-technique",
+        "minimal",
+        "attempts",
+    ] {
+        if matches.is_set(flag) {
+            return Err(Error::Usage(format!(
+                "--{} does not apply to killer puzzles",
+                flag
+            )));
+        }
+    }
+    let mut options = KillerOptions::new()
+        .shape(shape)
+        .givens(matches.value("clues")?.unwrap_or(0));
+    if let Some(cells) = matches.value("cage-size")? {
+        options = options.max_cage(cells);
+    }
+
+    let mut printer = Printer::new(matches)?;
+    eprintln!("seed: {}", seed);
+    let mut generator = Generator::from_seed(seed);
+    for _ in 0..count {
+        match generator.generate_killer(&options) {
+            Ok(puzzle) => printer.print_killer(&puzzle),
+            Err(error) => {
+                eprintln!("error: {}", error);
+                return Ok(EXIT_FAILURE);
+            }
+        }
+    }
+    Ok(EXIT_OK)
+}
+
+fn booklet(matches: &Matches) -> CommandResult {
+    let Some(path) = matches.value::<String>("output")? else {
+        return Err(Error::Usage("booklet needs --output".to_string()));
+    };
+    let seed = matches
+        .value("seed")?
+        .unwrap_or_else(|| rand::thread_rng().gen());
+    let mut options = BookletOptions::new(matches.value("count")?.unwrap_or(12))
+        .shape(matches.value("box")?.unwrap_or_default());
+    if let Some(tiers) = matches.value::<String>("difficulty")? {
+        let tiers = tiers
+            .split(',')
+            .map(|tier| tier.trim().parse())
+            .collect::<Result<Vec<Tier>, _>>()
+            .map_err(|error| format!("--difficulty: {}", error))?;
+        options = options.tiers(&tiers);
+    }
+    if let Some(per_page) = matches.value("per-page")? {
+        options = options.per_page(per_page);
+    }
+    if let Some(page_size) = matches.value("page-size")? {
+        options = options.page_size(page_size);
+    }
+    if let Some(title) = matches.value::<String>("title")? {
+        options = options.title(&title);
+    }
+    if let Some(attempts) = matches.value("attempts")? {
+        options = options.max_attempts(attempts);
+    }
+
+    eprintln!("seed: {}", seed);
+    let booklet = match Booklet::generate(&mut Generator::from_seed(seed), &options) {
+        Ok(booklet) => booklet,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            return Ok(EXIT_FAILURE);
+        }
+    };
+    let pdf = booklet.to_pdf();
+    if path == "-" {
+        io::stdout()
+            .write_all(&pdf)
+            .map_err(|error| Error::Io("stdout".to_string(), error))?;
+    } else {
+        fs::write(&path, pdf).map_err(|error| Error::Io(path.clone(), error))?;
+    }
+    eprintln!(
+        "{} puzzles on {} pages",
+        booklet.puzzles().len(),
+        booklet.pages()
+    );
+    Ok(EXIT_OK)
+}
+
+fn solve(matches: &Matches) -> CommandResult {
+    let max = matches.value("max")?.unwrap_or(1).max(1);
+    let mut printer = Printer::new(matches)?;
+    let solver = Solver::with_rules(Variant::from_matches(matches)?.rules(matches)?);
+    for_each_puzzle(matches, |grid| {
+        let solutions: Vec<Grid> = solver.solutions(grid).take(max + 1).collect();
+        if solutions.is_empty() {
+            eprintln!("error: puzzle has no solution");
+            return false;
+        }
+        if solutions.len() > max {
+            match max {
+                1 => eprintln!("warning: puzzle has more than one solution"),
+                _ => eprintln!("warning: puzzle has more than {} solutions", max),
+            }
+        }
+        for solution in solutions.iter().take(max) {
+            printer.print_solved(grid, solution);
+        }
+        true
+    })
+}
+
+/**
+ * Prints one line per puzzle: line number, solution (`-` if none), solution
+ * count and solving time. Malformed lines are reported on stderr, followed by
+ * the summary.
+ */
+fn bulk(matches: &Matches) -> CommandResult {
+    let mut solver = BulkSolver::new();
+    if let Some(threads) = matches.value("threads")? {
+        solver = solver.threads(threads);
+    }
+    let limit = matches.value_at_least("limit", 2)?.unwrap_or(2);
+    solver = solver.limit(limit);
+    let quiet = matches.is_set("quiet");
+
+    let path = match matches.positional.as_slice() {
+        [] => matches.value::<String>("input")?,
+        [path] => Some(path.clone()),
+        _ => return Err(Error::Usage("bulk takes at most one file".to_string())),
+    };
+    let (source, input): (String, Box<dyn BufRead>) = match path {
+        Some(path) if path != "-" => {
+            let file = File::open(&path).map_err(|error| Error::Io(path.clone(), error))?;
+            (path, Box::new(BufReader::new(file)))
+        }
+        _ => ("stdin".to_string(), Box::new(io::stdin().lock())),
+    };
+
+    let mut out = io::stdout().lock();
+    let summary = solver
+        .run(input, |report| {
+            let micros = report.elapsed.as_secs_f64() * 1e6;
+            let result = match &report.outcome {
+                Outcome::Solved {
+                    solution,
+                    solutions,
+                } => {
+                    let plus = if *solutions >= limit { "+" } else { "" };
+                    format!(
+                        "{}\t{}{}",
+                        Format::Line.write(solution).trim_end(),
+                        solutions,
+                        plus
+                    )
+                }
+                Outcome::Unsolvable => "-\t0".to_string(),
+                Outcome::Malformed(error) => {
+                    eprintln!("error: {}: {}", source, error);
+                    return;
+                }
+            };
+            if !quiet {
+                // A closed pipe only loses output; the summary still follows.
+                let _ = writeln!(out, "{}\t{}\t{:.0}us", report.line, result, micros);
+            }
+        })
+        .map_err(|error| Error::Io(source.clone(), error))?;
+
+    eprintln!(
+        "{} puzzles in {:.3}s with {} threads ({:.0} puzzles/s, {:.1}us each)",
+        summary.puzzles,
+        summary.elapsed.as_secs_f64(),
+        summary.threads,
+        summary.throughput(),
+        summary.solving.as_secs_f64() * 1e6 / summary.puzzles.max(1) as f64,
+    );
+    eprintln!(
+        "{} unique, {} multiple, {} unsolvable, {} malformed",
+        summary.unique, summary.multiple, summary.unsolvable, summary.malformed
+    );
+    Ok(if summary.failures() == 0 {
+        EXIT_OK
+    } else {
+        EXIT_FAILURE
+    })
+}
+
+fn count(matches: &Matches) -> CommandResult {
+    let limit = matches.value_at_least("limit", 2)?.unwrap_or(1000);
+    let solver = Solver::with_rules(Variant::from_matches(matches)?.rules(matches)?);
+    for_each_puzzle(matches, |grid| {
+        let count = solver.count_solutions(grid, limit);
+        if count >= limit {
+            println!("{}+", count);
+        } else {
+            println!("{}", count);
+        }
+        true
+    })
+}
+
+fn rate(matches: &Matches) -> CommandResult {
+    let verbose = matches.is_set("verbose");
+    for_each_puzzle(matches, |grid| match sudoku::rate(grid) {
+        Some(rating) => {
+            let hardest = rating.hardest.map_or("none", Technique::name);
+            println!("{}\t{}\t{}", rating.tier, rating.score, hardest);
+            if verbose {
+                for (technique, count) in &rating.counts {
+                    println!("  {:20}{}", technique.name(), count);
+                }
+            }
+            true
+        }
+        None => {
+            eprintln!("error: givens conflict");
+            false
+        }
+    })
+}
+
+/**
+ * Prints `valid`, or `invalid` followed by one indented line per problem.
+ */
+fn validate(matches: &Matches) -> CommandResult {
+    let rules = Variant::from_matches(matches)?.rules(matches)?;
+    for_each_puzzle(matches, |grid| {
+        let validation = sudoku::validate_with(grid, &rules);
+        if validation.is_valid() {
+            println!("valid");
+            return true;
+        }
+        println!("invalid");
+        for violation in &validation.violations {
+            println!("  {}", violation);
+        }
+        if validation.violations.is_empty() {
+            if let Some(solutions) = validation.solutions {
+                println!("  {}", solutions);
+            }
+        }
+        false
+    })
+}
+
+fn hint(matches: &Matches) -> CommandResult {
+    let all = matches.is_set("all");
+    let solver = LogicalSolver::new();
+    for_each_puzzle(matches, |grid| {
+        let Some(mut candidates) = Candidates::new(grid) else {
+            eprintln!("error: givens conflict");
+            return false;
+        };
+        let mut found = false;
+        while let Some(step) = solver.next_step(&candidates) {
+            println!("{}", step);
+            found = true;
+            if !all {
+                break;
+            }
+            candidates.apply(&step);
+        }
+        if !found && !candidates.is_solved() {
+            println!("no logical step found");
+        }
+        found || candidates.is_solved()
+    })
+}
+
+fn convert(matches: &Matches) -> CommandResult {
+    let mut printer = Printer::new(matches)?;
+    for_each_puzzle(matches, |grid| {
+        printer.print(grid);
+        true
+    })
+}
@@ -0,0 +1,323 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use crate::board::{bit, classic_layout, Layout};
+use crate::grid::{Grid, Shape};
+use crate::jigsaw::Regions;
+
+/**
+ * A rule of a Sudoku variant, consulted by the solver and the generator.
+ *
+ * Most rules only say that the digits in some groups of cells differ; those
+ * return the groups from `regions` and the search tracks them incrementally,
+ * like the rows, columns and boxes of the classic rules. Rules that are not
+ * about distinct digits, such as cage sums, narrow candidates through
+ * `eliminate` instead.
+ *
+ * Cells are numbered in row-major order, and digit sets are masks with bit
+ * `d - 1` set for digit `d`.
+ */
+pub trait Constraint: fmt::Debug + Send + Sync {
+    /**
+     * Returns a short name for the rule, such as `rows`.
+     */
+    fn name(&self) -> &str;
+
+    /**
+     * Returns the groups of cells whose digits must all differ.
+     * @param shape The shape of the grid.
+     */
+    fn regions(&self, shape: Shape) -> Vec<Vec<usize>> {
+        let _ = shape;
+        Vec::new()
+    }
+
+    /**
+     * Returns true if the rule narrows candidates through `eliminate`; the
+     * search skips `eliminate` for rules that do not.
+     */
+    fn eliminates(&self) -> bool {
+        false
+    }
+
+    /**
+     * Removes the candidates of a blank cell that the digits placed so far
+     * rule out.
+     * @param values The row-major cells of the grid, `0` for blanks.
+     * @param cell The blank cell.
+     * @param candidates The digits still allowed by the other rules.
+     * @return The digits this rule also allows.
+     */
+    fn eliminate(&self, values: &[u8], cell: usize, candidates: u32) -> u32 {
+        let _ = (values, cell);
+        candidates
+    }
+
+    /**
+     * Adapts a rule whose pruning depends on the rest of the rules, such as
+     * cage sums that also use the rows, columns and boxes. The search uses
+     * the adapted rule in its place.
+     * @param shape The shape of the grid.
+     * @param units The groups of cells of every constraint of the rules.
+     * @return The adapted rule, or `None` to use the rule as it is.
+     */
+    fn within(&self, shape: Shape, units: &[Vec<usize>]) -> Option<Arc<dyn Constraint>> {
+        let _ = (shape, units);
+        None
+    }
+
+    /**
+     * Returns true if the filled cells of a grid break none of the rule's
+     * conditions. Blank cells never break a rule. The default checks that no
+     * digit repeats within a region.
+     */
+    fn is_satisfied(&self, grid: &Grid) -> bool {
+        let values = grid.to_values();
+        self.regions(grid.shape()).iter().all(|region| {
+            let mut seen = 0u32;
+            region.iter().all(|&cell| match values[cell] {
+                0 => true,
+                value => {
+                    let bit = 1 << (value - 1);
+                    let fresh = seen & bit == 0;
+                    seen |= bit;
+                    fresh
+                }
+            })
+        })
+    }
+}
+
+/**
+ * Every row holds each digit once.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rows;
+
+impl Constraint for Rows {
+    fn name(&self) -> &str {
+        "rows"
+    }
+
+    fn regions(&self, shape: Shape) -> Vec<Vec<usize>> {
+        let size = shape.size();
+        (0..size)
+            .map(|row| (0..size).map(|col| row * size + col).collect())
+            .collect()
+    }
+}
+
+/**
+ * Every column holds each digit once.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Columns;
+
+impl Constraint for Columns {
+    fn name(&self) -> &str {
+        "columns"
+    }
+
+    fn regions(&self, shape: Shape) -> Vec<Vec<usize>> {
+        let size = shape.size();
+        (0..size)
+            .map(|col| (0..size).map(|row| row * size + col).collect())
+            .collect()
+    }
+}
+
+/**
+ * Every box of the shape holds each digit once.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Boxes;
+
+impl Constraint for Boxes {
+    fn name(&self) -> &str {
+        "boxes"
+    }
+
+    fn regions(&self, shape: Shape) -> Vec<Vec<usize>> {
+        let size = shape.size();
+        let mut boxes = vec![Vec::with_capacity(size); size];
+        for cell in 0..shape.cells() {
+            boxes[shape.box_of(cell / size, cell % size)].push(cell);
+        }
+        boxes
+    }
+}
+
+/**
+ * Both main diagonals hold each digit once, as in Sudoku-X.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Diagonals;
+
+impl Constraint for Diagonals {
+    fn name(&self) -> &str {
+        "diagonals"
+    }
+
+    fn regions(&self, shape: Shape) -> Vec<Vec<usize>> {
+        let size = shape.size();
+        vec![
+            (0..size).map(|i| i * size + i).collect(),
+            (0..size).map(|i| i * size + size - 1 - i).collect(),
+        ]
+    }
+}
+
+/**
+ * The set of constraints a grid must satisfy.
+ *
+ * The classic rules are `Rows`, `Columns` and `Boxes`; variants add their
+ * own constraints with `with`. Cloning is cheap, and clones share the cell
+ * layouts built for each grid shape.
+ */
+#[derive(Clone)]
+pub struct Rules {
+    constraints: Vec<Arc<dyn Constraint>>,
+    /** True while the rules are exactly the classic ones. */
+    classic: bool,
+    layouts: Arc<Mutex<Vec<Arc<Layout>>>>,
+}
+
+impl Rules {
+    /**
+     * Creates rules without any constraint, where any digit goes anywhere.
+     */
+    pub fn empty() -> Self {
+        Rules {
+            constraints: Vec::new(),
+            classic: false,
+            layouts: Arc::default(),
+        }
+    }
+
+    /**
+     * Creates the classic rules: rows, columns and boxes.
+     */
+    pub fn classic() -> Self {
+        let mut rules = Rules::empty().with(Rows).with(Columns).with(Boxes);
+        rules.classic = true;
+        rules
+    }
+
+    /**
+     * Creates the rules of Sudoku-X: the classic rules plus `Diagonals`.
+     */
+    pub fn diagonal() -> Self {
+        Rules::classic().with(Diagonals)
+    }
+
+    /**
+     * Creates the rules of a Jigsaw Sudoku: rows, columns and irregular
+     * regions in place of the boxes.
+     */
+    pub fn jigsaw(regions: Regions) -> Self {
+        Rules::empty().with(Rows).with(Columns).with(regions)
+    }
+
+    /**
+     * Adds a constraint.
+     */
+    pub fn with(self, constraint: impl Constraint + 'static) -> Self {
+        self.with_shared(Arc::new(constraint))
+    }
+
+    /**
+     * Adds a constraint that may also be part of other rules.
+     */
+    pub fn with_shared(mut self, constraint: Arc<dyn Constraint>) -> Self {
+        self.constraints.push(constraint);
+        self.classic = false;
+        self.layouts = Arc::default();
+        self
+    }
+
+    /**
+     * Returns the constraints in the order they were added.
+     */
+    pub fn constraints(&self) -> &[Arc<dyn Constraint>] {
+        &self.constraints
+    }
+
+    /**
+     * Returns true if these are exactly the classic rules, which lets the
+     * generator shuffle rows, columns and bands freely.
+     */
+    pub fn is_classic(&self) -> bool {
+        self.classic
+    }
+
+    /**
+     * Returns true if the filled cells of a grid break no constraint.
+     */
+    pub fn is_satisfied(&self, grid: &Grid) -> bool {
+        self.constraints
+            .iter()
+            .all(|constraint| constraint.is_satisfied(grid))
+    }
+
+    /**
+     * Checks if a digit can go in a blank cell: no region of the cell holds
+     * it already and no constraint eliminates it.
+     * @param grid The grid so far.
+     * @param row The row index of the cell.
+     * @param col The column index of the cell.
+     * @param digit The digit to check.
+     * @return True if placing the digit breaks no constraint, false for a
+     * digit outside `1..=size`.
+     */
+    pub fn is_safe(&self, grid: &Grid, row: usize, col: usize, digit: u8) -> bool {
+        if digit == 0 || usize::from(digit) > grid.size() {
+            return false;
+        }
+        let layout = self.layout(grid.shape());
+        let values = grid.to_values();
+        let cell = row * layout.size + col;
+        let bit = bit(digit);
+        let free = layout.units_of[cell]
+            .iter()
+            .flat_map(|&unit| &layout.units[unit])
+            .all(|&peer| peer == cell || values[peer] != digit);
+        free && layout
+            .eliminators
+            .iter()
+            .all(|constraint| constraint.eliminate(&values, cell, bit) & bit != 0)
+    }
+
+    /**
+     * Returns the cell layout of a shape under these rules, building it on
+     * first use.
+     */
+    pub(crate) fn layout(&self, shape: Shape) -> Arc<Layout> {
+        if self.classic {
+            return classic_layout(shape);
+        }
+        let mut layouts = self
+            .layouts
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        if let Some(layout) = layouts.iter().find(|layout| layout.shape == shape) {
+            return Arc::clone(layout);
+        }
+        let layout = Arc::new(Layout::new(shape, &self.constraints));
+        layouts.push(Arc::clone(&layout));
+        layout
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules::classic()
+    }
+}
+
+impl fmt::Debug for Rules {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.constraints.iter().map(|constraint| constraint.name()))
+            .finish()
+    }
+}
@@ -0,0 +1,306 @@
+/*!
+ * Vector drawing of a grid, shared by the SVG and PDF output.
+ */
+
+use crate::grid::{symbol, Grid};
+use crate::jigsaw::Regions;
+use crate::killer::Cage;
+use crate::logic::Candidates;
+
+/**
+ * The role of a stroke or glyph; each canvas maps it to a color.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Ink {
+    Line,
+    Given,
+    Solved,
+    Mark,
+    /** The background of shaded cells. */
+    Shade,
+    /** Cage outlines and sums. */
+    Cage,
+    /** The background of the drawing, behind labels that cross lines. */
+    Paper,
+}
+
+/**
+ * A surface with a top-left origin and y growing downwards.
+ */
+pub(crate) trait Canvas {
+    /**
+     * Strokes straight segments `(x1, y1, x2, y2)` with square caps.
+     */
+    fn lines(&mut self, segments: &[(f64, f64, f64, f64)], width: f64, ink: Ink);
+
+    /**
+     * Strokes segments as `lines` does, in dashes and gaps of `dash` each.
+     */
+    fn dashed_lines(&mut self, segments: &[(f64, f64, f64, f64)], width: f64, dash: f64, ink: Ink);
+
+    /**
+     * Fills a rectangle given by its top-left corner and size.
+     */
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, ink: Ink);
+
+    /**
+     * Writes text centred on a point.
+     */
+    fn text(&mut self, x: f64, y: f64, size: f64, bold: bool, ink: Ink, text: &str);
+}
+
+/**
+ * Returns the width of the thick box borders for a cell size. Borders stick
+ * out of the cell area by half of it, so drawings keep that much margin.
+ */
+pub(crate) fn border_width(cell: f64) -> f64 {
+    cell / 16.0
+}
+
+/**
+ * The markings of a variant drawn along with a grid.
+ */
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct Overlay<'a> {
+    /** Whether to shade the cells of both main diagonals. */
+    pub(crate) diagonals: bool,
+    /** Cages to outline, with their sums. */
+    pub(crate) cages: &'a [Cage],
+    /** Jigsaw regions whose boundaries replace the box borders. */
+    pub(crate) regions: Option<&'a Regions>,
+}
+
+/**
+ * Draws a grid with its top-left corner at `(x, y)`: the overlay, digits,
+ * optional pencil marks in blank cells, thin cell lines and thick box
+ * borders, or region borders for a jigsaw.
+ * @param givens The clues; other digits are drawn as solved.
+ * @param grid The digits to draw.
+ * @param marks The candidates to show in blank cells, if any.
+ * @param overlay The markings of the puzzle's variant.
+ */
+pub(crate) fn draw_grid(
+    canvas: &mut impl Canvas,
+    (x, y): (f64, f64),
+    cell: f64,
+    givens: &Grid,
+    grid: &Grid,
+    marks: Option<&Candidates>,
+    overlay: Overlay,
+) {
+    let shape = grid.shape();
+    let size = shape.size();
+    if overlay.diagonals {
+        for i in 0..size {
+            let top = y + i as f64 * cell;
+            canvas.rect(x + i as f64 * cell, top, cell, cell, Ink::Shade);
+            if 2 * i + 1 != size {
+                canvas.rect(
+                    x + (size - 1 - i) as f64 * cell,
+                    top,
+                    cell,
+                    cell,
+                    Ink::Shade,
+                );
+            }
+        }
+    }
+    for cage in overlay.cages {
+        draw_cage(canvas, (x, y), cell, size, cage);
+    }
+    for row in 0..size {
+        for col in 0..size {
+            let (left, top) = (x + col as f64 * cell, y + row as f64 * cell);
+            if let Some(digit) = grid.get(row, col) {
+                let given = givens.get(row, col) == Some(digit);
+                let ink = if given { Ink::Given } else { Ink::Solved };
+                canvas.text(
+                    left + cell / 2.0,
+                    top + cell / 2.0,
+                    cell * 0.65,
+                    given,
+                    ink,
+                    &symbol(digit).to_string(),
+                );
+            } else if let Some(marks) = marks {
+                let (box_rows, box_cols) = (shape.box_rows(), shape.box_cols());
+                let (step_x, step_y) = (cell / box_cols as f64, cell / box_rows as f64);
+                for digit in marks.candidates(row, col) {
+                    let index = usize::from(digit) - 1;
+                    let (mark_row, mark_col) = (index / box_cols, index % box_cols);
+                    canvas.text(
+                        left + (mark_col as f64 + 0.5) * step_x,
+                        top + (mark_row as f64 + 0.5) * step_y,
+                        step_x.min(step_y) * 0.7,
+                        false,
+                        Ink::Mark,
+                        &symbol(digit).to_string(),
+                    );
+                }
+            }
+        }
+    }
+
+    let side = size as f64 * cell;
+    if let Some(regions) = overlay.regions {
+        let thin: Vec<_> = (1..size)
+            .flat_map(|i| {
+                let at = i as f64 * cell;
+                [(x, y + at, x + side, y + at), (x + at, y, x + at, y + side)]
+            })
+            .collect();
+        canvas.lines(&thin, cell / 40.0, Ink::Line);
+        canvas.lines(
+            &region_borders((x, y), cell, regions),
+            border_width(cell),
+            Ink::Line,
+        );
+    } else {
+        for (width, boxed) in [(cell / 40.0, false), (border_width(cell), true)] {
+            let mut segments = Vec::new();
+            for i in 0..=size {
+                let at = i as f64 * cell;
+                if (i % shape.box_rows() == 0) == boxed {
+                    segments.push((x, y + at, x + side, y + at));
+                }
+                if (i % shape.box_cols() == 0) == boxed {
+                    segments.push((x + at, y, x + at, y + side));
+                }
+            }
+            canvas.lines(&segments, width, Ink::Line);
+        }
+    }
+}
+
+/**
+ * Returns the segments of the outer frame and of every cell edge between two
+ * regions, joining edges that continue one another along a line.
+ */
+fn region_borders((x, y): (f64, f64), cell: f64, regions: &Regions) -> Vec<(f64, f64, f64, f64)> {
+    let size = regions.shape().size();
+    // Whether the edge at `along` on line `line` separates regions, lines
+    // being horizontal when `across` is false.
+    let border = |across: bool, line: usize, along: usize| {
+        if line == 0 || line == size {
+            return true;
+        }
+        match across {
+            false => regions.region_of(line - 1, along) != regions.region_of(line, along),
+            true => regions.region_of(along, line - 1) != regions.region_of(along, line),
+        }
+    };
+    let mut segments = Vec::new();
+    for across in [false, true] {
+        for line in 0..=size {
+            let mut along = 0;
+            while along < size {
+                if !border(across, line, along) {
+                    along += 1;
+                    continue;
+                }
+                let start = along;
+                while along < size && border(across, line, along) {
+                    along += 1;
+                }
+                let at = line as f64 * cell;
+                let (from, to) = (start as f64 * cell, along as f64 * cell);
+                segments.push(match across {
+                    false => (x + from, y + at, x + to, y + at),
+                    true => (x + at, y + from, x + at, y + to),
+                });
+            }
+        }
+    }
+    segments
+}
+
+/**
+ * Outlines a cage with a dashed line inset from the cell edges, and writes
+ * its sum in the top-left corner of its first cell.
+ * @param size The number of cells on a side of the grid.
+ */
+fn draw_cage(canvas: &mut impl Canvas, (x, y): (f64, f64), cell: f64, size: usize, cage: &Cage) {
+    let inset = cell * 0.1;
+    let half = cell / 2.0;
+    let inside = |row: isize, col: isize| {
+        (0..size as isize).contains(&row)
+            && (0..size as isize).contains(&col)
+            && cage.cells().contains(&(row as usize * size + col as usize))
+    };
+    let mut segments = Vec::new();
+    for &member in cage.cells() {
+        let (row, col) = ((member / size) as isize, (member % size) as isize);
+        let centre_x = x + (col as f64 + 0.5) * cell;
+        let centre_y = y + (row as f64 + 0.5) * cell;
+        // Each side as an outward normal and the direction along it.
+        for ((normal_row, normal_col), (along_row, along_col)) in [
+            ((-1, 0), (0, 1)),
+            ((1, 0), (0, 1)),
+            ((0, -1), (1, 0)),
+            ((0, 1), (1, 0)),
+        ] {
+            if inside(row + normal_row, col + normal_col) {
+                continue;
+            }
+            // An end stops short of an outer corner, meets the neighbour's
+            // side along a straight edge, and reaches past an inner corner.
+            let reach = |sign: isize| {
+                let (side_row, side_col) = (row + sign * along_row, col + sign * along_col);
+                if !inside(side_row, side_col) {
+                    half - inset
+                } else if inside(side_row + normal_row, side_col + normal_col) {
+                    half + inset
+                } else {
+                    half
+                }
+            };
+            let offset = half - inset;
+            let (base_x, base_y) = (
+                centre_x + normal_col as f64 * offset,
+                centre_y + normal_row as f64 * offset,
+            );
+            let (start, end) = (reach(-1), reach(1));
+            segments.push((
+                base_x - along_col as f64 * start,
+                base_y - along_row as f64 * start,
+                base_x + along_col as f64 * end,
+                base_y + along_row as f64 * end,
+            ));
+        }
+    }
+    canvas.dashed_lines(&segments, cell / 40.0, cell / 14.0, Ink::Cage);
+
+    let first = cage.cells()[0];
+    let (left, top) = (
+        x + (first % size) as f64 * cell,
+        y + (first / size) as f64 * cell,
+    );
+    let label = cage.sum().to_string();
+    let height = cell * 0.24;
+    // Digits are about 0.56 em wide in the usual sans-serif fonts.
+    let width = height * 0.56 * label.len() as f64;
+    let pad = cell * 0.03;
+    canvas.rect(
+        left + inset - pad,
+        top + inset - pad,
+        width + 2.0 * pad,
+        height + 2.0 * pad,
+        Ink::Paper,
+    );
+    canvas.text(
+        left + inset + width / 2.0,
+        top + inset + height / 2.0,
+        height,
+        false,
+        Ink::Cage,
+        &label,
+    );
+}
+
+/**
+ * Formats a coordinate with at most two decimals and no trailing zeros.
+ */
+pub(crate) fn number(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
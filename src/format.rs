@@ -0,0 +1,352 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::grid::{nearest_cell_count, symbol, symbol_value, Grid, Shape};
+
+/**
+ * A text format for a single grid.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /** All cells on one line, `.` for blanks. */
+    Line,
+    /** SadMan `.sdk`: one line per row, `.` for blanks. */
+    Sdk,
+    /** Simple Sudoku `.ss`: `.sdk` rows split into boxes by `|` and `-` lines. */
+    SimpleSudoku,
+}
+
+impl Format {
+    /** Every format. */
+    pub const ALL: [Format; 3] = [Format::Line, Format::Sdk, Format::SimpleSudoku];
+
+    /**
+     * Returns the short name of the format, which is also its file extension
+     * for the multi-line formats.
+     */
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Line => "line",
+            Format::Sdk => "sdk",
+            Format::SimpleSudoku => "ss",
+        }
+    }
+
+    /**
+     * Writes a grid in this format.
+     * @return The text, ending with a newline.
+     */
+    pub fn write(self, grid: &Grid) -> String {
+        let shape = grid.shape();
+        let size = shape.size();
+        let values = grid.to_values();
+        let cell = |i: usize| match values[i] {
+            0 => '.',
+            digit => symbol(digit),
+        };
+        let mut text = String::with_capacity(2 * values.len());
+        match self {
+            Format::Line => text.extend((0..values.len()).map(cell)),
+            Format::Sdk => {
+                for row in 0..size {
+                    text.extend((0..size).map(|col| cell(row * size + col)));
+                    text.push('\n');
+                }
+                text.pop();
+            }
+            Format::SimpleSudoku => {
+                for row in 0..size {
+                    if row > 0 && row % shape.box_rows() == 0 {
+                        text.push_str(&"-".repeat(size + shape.box_rows() - 1));
+                        text.push('\n');
+                    }
+                    for col in 0..size {
+                        if col > 0 && col % shape.box_cols() == 0 {
+                            text.push('|');
+                        }
+                        text.push(cell(row * size + col));
+                    }
+                    text.push('\n');
+                }
+                text.pop();
+            }
+        }
+        text.push('\n');
+        text
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Format::ALL
+            .into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown format '{}'", s))
+    }
+}
+
+/**
+ * What was wrong with a grid's text.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /** A character that is neither a cell nor a decoration. */
+    InvalidCharacter(char),
+    /** A digit larger than the size of the grid, such as `A` in a 9x9 grid. */
+    DigitOutOfRange(char),
+    /** The text ended after this many cells. */
+    TooFewCells(usize),
+    /** A cell beyond the last one of the grid. */
+    TooManyCells,
+}
+
+/**
+ * A parse failure and where it happened. Lines and columns count from 1;
+ * for `TooFewCells` they point just past the end of the text.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match self.kind {
+            ParseErrorKind::InvalidCharacter(c) => write!(f, "unexpected character {:?}", c),
+            ParseErrorKind::DigitOutOfRange(c) => {
+                write!(f, "digit {:?} is too large for the grid", c)
+            }
+            ParseErrorKind::TooFewCells(found) => {
+                write!(f, "the grid ends after {} cells", found)
+            }
+            ParseErrorKind::TooManyCells => f.write_str("more cells than the grid has"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/**
+ * Returns true for characters that only decorate a grid: whitespace and the
+ * box separators of the `.ss` format.
+ */
+fn is_decoration(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '|' | '-' | '+')
+}
+
+/**
+ * Returns true for lines that are comments or metadata, such as the `#A`
+ * author lines of `.sdk` files.
+ */
+fn is_comment(line: &str) -> bool {
+    line.trim_start().starts_with('#')
+}
+
+/**
+ * Counts the cells of a line, or returns `None` for a comment.
+ */
+fn count_cells(line: &str) -> Option<usize> {
+    if is_comment(line) {
+        return None;
+    }
+    Some(line.chars().filter(|&c| !is_decoration(c)).count())
+}
+
+/**
+ * Parses a grid in any supported format and size.
+ *
+ * Digits `1`-`9`, then letters `A` (10) to `P` (25) in either case, are
+ * givens and `0` or `.` are blanks. Whitespace, the `|`, `-` and `+`
+ * decorations and lines starting with `#` are skipped, so the single-line,
+ * `.sdk` and `.ss` formats all parse.
+ *
+ * The shape is inferred: a grid written over several lines has as many
+ * rows as its first row has cells, and a single line holds the cells of
+ * the nearest supported size, so 81 cells make a 9x9 grid and 36 a 6x6.
+ *
+ * @param text The grid.
+ * @return The grid, or the first problem with the text and where it is.
+ */
+pub fn parse(text: &str) -> Result<Grid, ParseError> {
+    parse_lines(text.lines().enumerate(), None)
+}
+
+/**
+ * Parses a grid of a known shape, as `parse` does without the guessing.
+ * @param text The grid.
+ * @param shape The box dimensions of the grid.
+ */
+pub fn parse_shaped(text: &str, shape: Shape) -> Result<Grid, ParseError> {
+    parse_lines(text.lines().enumerate(), Some(shape))
+}
+
+/**
+ * Parses numbered lines as one grid.
+ * @param lines The lines with their zero-based line numbers.
+ * @param shape The shape of the grid, `None` to infer it from the text.
+ */
+fn parse_lines<'a>(
+    lines: impl Iterator<Item = (usize, &'a str)>,
+    shape: Option<Shape>,
+) -> Result<Grid, ParseError> {
+    let mut cells: Vec<(u8, char, usize, usize)> = Vec::new();
+    let mut first_row = None;
+    let mut rows = 0;
+    let mut end = (1, 1);
+    for (number, line) in lines {
+        end = (number + 1, line.chars().count() + 1);
+        match count_cells(line) {
+            None | Some(0) => continue,
+            Some(count) => {
+                first_row.get_or_insert(count);
+                rows += 1;
+            }
+        }
+        for (column, c) in line.chars().enumerate() {
+            let value = match c {
+                '.' => 0,
+                _ if is_decoration(c) => continue,
+                _ => match symbol_value(c).filter(|&value| usize::from(value) <= Shape::MAX_SIZE) {
+                    Some(value) => value,
+                    None => {
+                        return Err(ParseError {
+                            kind: ParseErrorKind::InvalidCharacter(c),
+                            line: number + 1,
+                            column: column + 1,
+                        })
+                    }
+                },
+            };
+            cells.push((value, c, number + 1, column + 1));
+        }
+    }
+    let expected = match (shape, first_row) {
+        (Some(shape), _) => shape.cells(),
+        (None, Some(size)) if rows > 1 && Shape::for_size(size).is_some() => size * size,
+        (None, _) => nearest_cell_count(cells.len()),
+    };
+    if let Some(&(_, _, line, column)) = cells.get(expected) {
+        return Err(ParseError {
+            kind: ParseErrorKind::TooManyCells,
+            line,
+            column,
+        });
+    }
+    if cells.len() < expected {
+        return Err(ParseError {
+            kind: ParseErrorKind::TooFewCells(cells.len()),
+            line: end.0,
+            column: end.1,
+        });
+    }
+    let shape = shape
+        .or_else(|| Shape::for_cells(expected))
+        .expect("supported cell count");
+    if let Some(&(_, c, line, column)) = cells
+        .iter()
+        .find(|&&(value, ..)| usize::from(value) > shape.size())
+    {
+        return Err(ParseError {
+            kind: ParseErrorKind::DigitOutOfRange(c),
+            line,
+            column,
+        });
+    }
+    let values: Vec<u8> = cells.iter().map(|&(value, ..)| value).collect();
+    Ok(Grid::with_values(shape, &values).expect("parsed digits are in range"))
+}
+
+/**
+ * Parses a collection of grids, such as a file with one puzzle per line or
+ * a sequence of multi-line grids.
+ *
+ * A line that holds the cells of a whole grid is a grid of its own. Any
+ * other line starts a multi-line grid with as many rows as it has cells,
+ * which ends once all its cells have been read or at a blank line, so
+ * single-line puzzles, `.sdk` and `.ss` grids can be mixed. A line of 16
+ * cells is a 4x4 grid unless it is spaced, split into boxes or holds a
+ * digit above 4, as the rows of a 16x16 grid do; use `parse_many_shaped`
+ * to settle the rest. Line numbers in errors refer to the whole text.
+ *
+ * @param text The grids.
+ * @return Each grid or the error that stopped it, in order.
+ */
+pub fn parse_many(text: &str) -> Vec<Result<Grid, ParseError>> {
+    split_grids(text, None)
+}
+
+/**
+ * Parses a collection of grids that all have a known shape.
+ * @param text The grids.
+ * @param shape The box dimensions of every grid.
+ * @return Each grid or the error that stopped it, in order.
+ */
+pub fn parse_many_shaped(text: &str, shape: Shape) -> Vec<Result<Grid, ParseError>> {
+    split_grids(text, Some(shape))
+}
+
+/**
+ * Returns the number of cells of the grid that a line starts, or `None` if
+ * the line does not tell.
+ */
+fn grid_cells(line: &str, count: usize) -> Option<usize> {
+    let largest = line.chars().filter_map(symbol_value).max().unwrap_or(0);
+    let whole = Shape::for_cells(count).is_some_and(|shape| usize::from(largest) <= shape.size());
+    let row = Shape::for_size(count).is_some();
+    let spaced = line
+        .trim()
+        .contains(|c: char| c.is_whitespace() || c == '|');
+    match (whole, row) {
+        (true, true) if spaced => Some(count * count),
+        (true, _) => Some(count),
+        (false, true) => Some(count * count),
+        (false, false) => None,
+    }
+}
+
+fn split_grids(text: &str, shape: Option<Shape>) -> Vec<Result<Grid, ParseError>> {
+    let mut grids = Vec::new();
+    let mut block: Vec<(usize, &str)> = Vec::new();
+    let mut cells = 0;
+    let mut expected = 0;
+    for (number, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || is_comment(line) {
+            if !block.is_empty() && line.trim().is_empty() {
+                grids.push(parse_lines(block.drain(..), shape));
+                cells = 0;
+            }
+            continue;
+        }
+        let count = count_cells(line).unwrap_or(0);
+        if block.is_empty() {
+            if count == 0 {
+                continue;
+            }
+            expected = match shape {
+                Some(shape) => shape.cells(),
+                None => grid_cells(line, count).unwrap_or(count),
+            };
+        }
+        block.push((number, line));
+        cells += count;
+        if cells >= expected {
+            grids.push(parse_lines(block.drain(..), shape));
+            cells = 0;
+        }
+    }
+    if !block.is_empty() {
+        grids.push(parse_lines(block.drain(..), shape));
+    }
+    grids
+}
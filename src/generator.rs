@@ -0,0 +1,779 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::board::{digits, Board};
+use crate::constraint::Rules;
+use crate::grid::{Grid, Shape};
+use crate::jigsaw::{self, Regions};
+use crate::killer::{self, KillerOptions, KillerPuzzle};
+use crate::logic::Technique;
+use crate::puzzle::Puzzle;
+use crate::rating::{rate, Rating, Tier};
+use crate::solver::Solver;
+use crate::symmetry::Symmetry;
+
+/**
+ * What to generate and how much effort to spend on it.
+ */
+#[derive(Debug, Clone)]
+pub struct PuzzleOptions {
+    clues: usize,
+    shape: Shape,
+    rules: Rules,
+    max_attempts: usize,
+    time_limit: Option<Duration>,
+    symmetry: Symmetry,
+    minimal: bool,
+    difficulty: Option<Tier>,
+    technique: Option<Technique>,
+    max_technique: Option<Technique>,
+}
+
+impl PuzzleOptions {
+    /**
+     * Asks for a 9x9 puzzle with exactly `clues` givens, within 100
+     * attempts, no time limit and no symmetry.
+     */
+    pub fn new(clues: usize) -> Self {
+        PuzzleOptions {
+            clues,
+            shape: Shape::CLASSIC,
+            rules: Rules::classic(),
+            max_attempts: 100,
+            time_limit: None,
+            symmetry: Symmetry::None,
+            minimal: false,
+            difficulty: None,
+            technique: None,
+            max_technique: None,
+        }
+    }
+
+    /**
+     * Sets the shape of the grid, such as 2x3 boxes for a 6x6 puzzle.
+     */
+    pub fn shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /**
+     * Sets the rules the puzzle follows, the classic ones by default. The
+     * solution is filled and uniqueness is checked under these rules.
+     * Ratings only know deductions from rows, columns and boxes, so
+     * `difficulty`, `technique` and `max_technique` need the classic rules.
+     */
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /**
+     * Sets the number of fresh grids to try before giving up.
+     */
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = attempts;
+        self
+    }
+
+    /**
+     * Sets the wall-clock time after which generation gives up.
+     */
+    pub fn time_limit(mut self, limit: Duration) -> Self {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /**
+     * Sets the symmetry the clue pattern must have. Some clue counts cannot
+     * be reached under a symmetry whose orbits are all larger than one cell.
+     */
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    /**
+     * Requires every clue of the puzzle to be necessary. The clue count then
+     * becomes an upper bound: clues are removed for as long as the solution
+     * stays unique, and the attempt succeeds if the minimal puzzle has at
+     * most the requested number. With a symmetry, attempts whose symmetric
+     * result still has a redundant clue are rejected.
+     */
+    pub fn minimal(mut self, minimal: bool) -> Self {
+        self.minimal = minimal;
+        self
+    }
+
+    /**
+     * Requires the puzzle to rate in a given tier. The clue count then
+     * becomes an upper bound: clues are removed while the solution stays
+     * unique and the rating stays at or below the tier, and the attempt
+     * succeeds if it ends in exactly that tier.
+     */
+    pub fn difficulty(mut self, tier: Tier) -> Self {
+        self.difficulty = Some(tier);
+        self
+    }
+
+    /**
+     * Requires the logical solve path to use a technique. Unless a harder
+     * `max_technique` is set, it must also be the hardest technique on the
+     * path. As with `difficulty`, the clue count becomes an upper bound.
+     */
+    pub fn technique(mut self, technique: Technique) -> Self {
+        self.technique = Some(technique);
+        self
+    }
+
+    /**
+     * Sets the hardest technique the logical solve path may use; the puzzle
+     * must be solvable with techniques up to this one.
+     */
+    pub fn max_technique(mut self, technique: Technique) -> Self {
+        self.max_technique = Some(technique);
+        self
+    }
+}
+
+/**
+ * The hardest solve a puzzle may need while clues are being removed.
+ */
+#[derive(Debug, Clone, Copy, Default)]
+struct Ceiling {
+    tier: Option<Tier>,
+    technique: Option<Technique>,
+}
+
+impl Ceiling {
+    fn of(options: &PuzzleOptions) -> Self {
+        Ceiling {
+            tier: options.difficulty,
+            technique: options.max_technique.or(options.technique),
+        }
+    }
+
+    fn is_none(&self) -> bool {
+        self.tier.is_none() && self.technique.is_none()
+    }
+
+    fn admits(&self, rating: &Rating) -> bool {
+        self.tier.is_none_or(|tier| rating.tier <= tier)
+            && self.technique.is_none_or(|max| {
+                rating.solved && rating.hardest.is_none_or(|hardest| hardest <= max)
+            })
+    }
+}
+
+/**
+ * Why `Generator::generate` found no puzzle.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GenerationError {
+    /** The clue target is larger than the grid. */
+    InvalidTarget { target: usize, cells: usize },
+    /**
+     * Every attempt got stuck above the target; `reached` is the fewest clues
+     * seen, counting only minimal puzzles when those were asked for.
+     */
+    Exhausted {
+        target: usize,
+        reached: usize,
+        attempts: usize,
+    },
+    /** The time limit ran out; `reached` is the fewest clues seen. */
+    TimedOut {
+        target: usize,
+        reached: usize,
+        attempts: usize,
+    },
+    /**
+     * No attempt within the clue limit rated in the wanted tier; `seen`
+     * counts the tiers the attempts ended in instead.
+     */
+    DifficultyNotReached {
+        wanted: Tier,
+        attempts: usize,
+        seen: BTreeMap<Tier, usize>,
+    },
+    /**
+     * No attempt within the limits needed the required technique; `seen`
+     * counts the hardest techniques of the solvable attempts instead.
+     */
+    TechniqueNotReached {
+        required: Technique,
+        attempts: usize,
+        seen: BTreeMap<Technique, usize>,
+    },
+    /** The required technique is harder than the maximum technique. */
+    InvalidTechniques { required: Technique, max: Technique },
+    /** A difficulty or technique was asked for under non-classic rules. */
+    UnratedRules,
+    /** The rules admit no filled grid of the requested shape. */
+    Unsatisfiable,
+    /** A booklet was asked for without any puzzle or tier. */
+    EmptyBooklet,
+    /** More puzzles were asked for on a booklet page than fit readably. */
+    CrowdedPage { per_page: usize, max: usize },
+}
+
+impl fmt::Display for GenerationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GenerationError::InvalidTarget { target, cells } => {
+                write!(f, "cannot place {} clues in a {}-cell grid", target, cells)
+            }
+            GenerationError::Exhausted {
+                target,
+                reached,
+                attempts,
+            } => write!(
+                f,
+                "no puzzle with {} clues after {} attempts; fewest reached was {}",
+                target, attempts, reached
+            ),
+            GenerationError::TimedOut {
+                target,
+                reached,
+                attempts,
+            } => write!(
+                f,
+                "timed out after {} attempts looking for {} clues; fewest reached was {}",
+                attempts, target, reached
+            ),
+            GenerationError::DifficultyNotReached {
+                wanted,
+                attempts,
+                seen,
+            } => {
+                write!(f, "no {} puzzle after {} attempts; got", wanted, attempts)?;
+                for (i, (tier, count)) in seen.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(f, "{}{} {}", separator, count, tier)?;
+                }
+                if seen.is_empty() {
+                    f.write_str(" nothing within the clue limit")?;
+                }
+                Ok(())
+            }
+            GenerationError::TechniqueNotReached {
+                required,
+                attempts,
+                seen,
+            } => {
+                write!(
+                    f,
+                    "no puzzle needing {} after {} attempts; hardest steps were",
+                    required, attempts
+                )?;
+                for (i, (technique, count)) in seen.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(f, "{}{} {}", separator, technique, count)?;
+                }
+                if seen.is_empty() {
+                    f.write_str(" never reached within the limits")?;
+                }
+                Ok(())
+            }
+            GenerationError::InvalidTechniques { required, max } => write!(
+                f,
+                "required technique {} is harder than the maximum {}",
+                required, max
+            ),
+            GenerationError::UnratedRules => {
+                f.write_str("difficulties and techniques are only rated under the classic rules")
+            }
+            GenerationError::Unsatisfiable => {
+                f.write_str("the rules admit no solution for this grid shape")
+            }
+            GenerationError::EmptyBooklet => {
+                f.write_str("a booklet needs at least one puzzle and one tier")
+            }
+            GenerationError::CrowdedPage { per_page, max } => write!(
+                f,
+                "{} puzzles do not fit on a page; at most {} do at this size",
+                per_page, max
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenerationError {}
+
+/**
+ * Generates full Sudoku solutions and puzzles with a unique solution.
+ *
+ * All randomness comes from the generator's `R`. The default `ChaCha8Rng`
+ * produces the same sequence on every platform, so a generator built with
+ * `from_seed` reproduces the same grids and puzzles everywhere.
+ */
+#[derive(Debug)]
+pub struct Generator<R = ChaCha8Rng> {
+    rng: R,
+    solver: Solver,
+}
+
+impl Generator {
+    /**
+     * Creates a generator with a random seed.
+     */
+    pub fn new() -> Self {
+        Generator::from_seed(rand::thread_rng().gen())
+    }
+
+    /**
+     * Creates a reproducible generator.
+     * @param seed The seed; equal seeds give equal output.
+     */
+    pub fn from_seed(seed: u64) -> Self {
+        Generator::with_rng(ChaCha8Rng::seed_from_u64(seed))
+    }
+}
+
+impl<R: Rng> Generator<R> {
+    /**
+     * Creates a generator drawing from the given random number generator.
+     */
+    pub fn with_rng(rng: R) -> Self {
+        Generator {
+            rng,
+            solver: Solver::new(),
+        }
+    }
+
+    /**
+     * Creates a new Sudoku grid and fills it.
+     * @return A newly generated, completely filled Sudoku grid.
+     */
+    pub fn solution(&mut self) -> Grid {
+        self.solution_with_shape(Shape::CLASSIC)
+    }
+
+    /**
+     * Creates a grid of a given shape and fills it.
+     * @param shape The box dimensions of the grid.
+     * @return A newly generated, completely filled grid of that shape.
+     */
+    pub fn solution_with_shape(&mut self, shape: Shape) -> Grid {
+        self.solution_with_rules(shape, &Rules::classic())
+            .expect("classic grids can be filled")
+    }
+
+    /**
+     * Creates a grid of a given shape and fills it under the rules of a
+     * variant.
+     * @param shape The box dimensions of the grid.
+     * @param rules The constraints the filled grid must satisfy.
+     * @return A newly generated, completely filled grid, or `None` if the
+     * rules admit none.
+     */
+    pub fn solution_with_rules(&mut self, shape: Shape, rules: &Rules) -> Option<Grid> {
+        let mut grid = Grid::new(shape);
+        self.fill(&mut grid, rules).then_some(grid)
+    }
+
+    /**
+     * Creates a random layout of jigsaw regions, connected groups of cells
+     * that take the place of the boxes. Some filled grid fits the layout, so
+     * `generate` succeeds with `Rules::jigsaw` of it.
+     *
+     * Irregular layouts admit far fewer filled grids than boxes do, and the
+     * search for one slows down sharply with the size: 9x9 and 12x12 take
+     * milliseconds, while 16x16 can take minutes.
+     *
+     * @param shape The shape of the grid; only its size matters.
+     * @return The regions.
+     */
+    pub fn regions(&mut self, shape: Shape) -> Regions {
+        let mut regions = Regions::boxes(shape);
+        for _ in 0..RESHAPES {
+            let solution = self
+                .solution_with_rules(shape, &Rules::jigsaw(regions.clone()))
+                .expect("reshaped regions fit a filled grid");
+            regions = jigsaw::reshape(&mut self.rng, &regions, &solution);
+        }
+        regions
+    }
+
+    /**
+     * Generates a puzzle with exactly the requested number of clues (at most
+     * that many for a minimal puzzle or one with a difficulty or technique
+     * constraint).
+     *
+     * Each attempt fills a fresh grid and removes clues in random order while
+     * the solution stays unique. An attempt that gets stuck above the target,
+     * or ends in the wrong tier, is abandoned and the next one starts from a
+     * new grid, until the attempt or time budget of the options runs out.
+     *
+     * @param options The clue target, constraints and budget.
+     * @return The puzzle, or why no matching puzzle was found.
+     */
+    pub fn generate(&mut self, options: &PuzzleOptions) -> Result<Puzzle, GenerationError> {
+        let target = options.clues;
+        let cells = options.shape.cells();
+        if target > cells {
+            return Err(GenerationError::InvalidTarget { target, cells });
+        }
+        let ceiling = Ceiling::of(options);
+        if let (Some(required), Some(max)) = (options.technique, ceiling.technique) {
+            if required > max {
+                return Err(GenerationError::InvalidTechniques { required, max });
+            }
+        }
+        if !ceiling.is_none() && !options.rules.is_classic() {
+            return Err(GenerationError::UnratedRules);
+        }
+        let deadline = options.time_limit.map(|limit| Instant::now() + limit);
+        let exact = !options.minimal && ceiling.is_none();
+        let floor = if exact { target } else { 0 };
+        let mut reached = cells;
+        let mut tiers = BTreeMap::new();
+        let mut bottlenecks = BTreeMap::new();
+        let solver = Solver::with_rules(options.rules.clone());
+        for attempt in 1..=options.max_attempts {
+            let solution = self
+                .solution_with_rules(options.shape, &options.rules)
+                .ok_or(GenerationError::Unsatisfiable)?;
+            let removal = Removal {
+                solver: &solver,
+                filled: floor,
+                symmetry: options.symmetry,
+                ceiling,
+                deadline,
+            };
+            let givens = self.remove_until(&solution, &removal);
+            let complete = deadline.is_none_or(|deadline| Instant::now() < deadline);
+            let clues = givens.filled_count();
+            if complete && (!options.minimal || solver.is_minimal(&givens)) {
+                reached = reached.min(clues);
+                let fits = if exact {
+                    clues == target
+                } else {
+                    clues <= target
+                };
+                if fits && ceiling.is_none() {
+                    return Ok(Puzzle::from_parts(givens, solution));
+                }
+                if fits {
+                    let Some(rating) = rate(&givens) else {
+                        continue;
+                    };
+                    let tier_matches = options.difficulty.is_none_or(|tier| tier == rating.tier);
+                    let technique_used = options
+                        .technique
+                        .is_none_or(|technique| rating.counts.contains_key(&technique));
+                    if tier_matches && technique_used && ceiling.admits(&rating) {
+                        return Ok(Puzzle::from_parts(givens, solution));
+                    }
+                    *tiers.entry(rating.tier).or_insert(0) += 1;
+                    if let Some(hardest) = rating.hardest.filter(|_| rating.solved) {
+                        *bottlenecks.entry(hardest).or_insert(0) += 1;
+                    }
+                }
+            }
+            if !complete {
+                return Err(GenerationError::TimedOut {
+                    target,
+                    reached,
+                    attempts: attempt,
+                });
+            }
+        }
+        let attempts = options.max_attempts;
+        match (options.technique, options.difficulty) {
+            _ if reached > target => Err(GenerationError::Exhausted {
+                target,
+                reached,
+                attempts,
+            }),
+            (Some(required), _) => Err(GenerationError::TechniqueNotReached {
+                required,
+                attempts,
+                seen: bottlenecks,
+            }),
+            (None, Some(wanted)) => Err(GenerationError::DifficultyNotReached {
+                wanted,
+                attempts,
+                seen: tiers,
+            }),
+            (None, None) => Err(GenerationError::Exhausted {
+                target,
+                reached,
+                attempts,
+            }),
+        }
+    }
+
+    /**
+     * Generates a Killer Sudoku: a fresh solution divided into cages that
+     * make it unique, together with the requested number of givens.
+     *
+     * Cages are grown at random and split while the puzzle has a second
+     * solution, so generation always succeeds; more givens leave room for
+     * larger cages.
+     *
+     * @param options The shape, givens and largest cage.
+     * @return The puzzle, or `InvalidTarget` if there are more givens than
+     * cells.
+     */
+    pub fn generate_killer(
+        &mut self,
+        options: &KillerOptions,
+    ) -> Result<KillerPuzzle, GenerationError> {
+        let cells = options.shape.cells();
+        if options.givens > cells {
+            return Err(GenerationError::InvalidTarget {
+                target: options.givens,
+                cells,
+            });
+        }
+        let solution = self.solution_with_shape(options.shape);
+        Ok(killer::cage(&mut self.rng, solution, options))
+    }
+
+    /**
+     * Removes cells from the Sudoku grid, keeping its solution unique.
+     *
+     * Every filled cell is tried once, in random order, so this always
+     * terminates; it stops early once only `filled` cells remain, and may
+     * finish above `filled` when no further cell can be removed.
+     *
+     * @param grid The Sudoku grid to start from.
+     * @param filled The desired number of filled cells.
+     * @return The grid with cells removed.
+     */
+    pub fn remove_cells(&mut self, grid: &Grid, filled: usize) -> Grid {
+        self.remove_symmetric(grid, filled, Symmetry::None)
+    }
+
+    /**
+     * Removes cells as `remove_cells` does, a whole orbit of the symmetry at
+     * a time, skipping orbits that would take the grid below `filled`.
+     */
+    pub fn remove_symmetric(&mut self, grid: &Grid, filled: usize, symmetry: Symmetry) -> Grid {
+        let solver = self.solver.clone();
+        let removal = Removal {
+            solver: &solver,
+            filled,
+            symmetry,
+            ceiling: Ceiling::default(),
+            deadline: None,
+        };
+        self.remove_until(grid, &removal)
+    }
+
+    fn remove_until(&mut self, grid: &Grid, removal: &Removal) -> Grid {
+        let Removal {
+            solver,
+            filled,
+            symmetry,
+            ceiling,
+            deadline,
+        } = *removal;
+        let mut grid = grid.clone();
+        let mut orbits = symmetry.orbits_in(grid.size());
+        orbits.shuffle(&mut self.rng);
+        let mut count = grid.filled_count();
+        for orbit in orbits {
+            if count <= filled || deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+            let backup: Vec<(usize, usize, u8)> = orbit
+                .iter()
+                .filter_map(|&(row, col)| grid.get(row, col).map(|value| (row, col, value)))
+                .collect();
+            if backup.is_empty() || count - backup.len() < filled {
+                continue;
+            }
+            for &(row, col, _) in &backup {
+                grid.clear(row, col);
+            }
+            let within = |grid: &Grid| {
+                ceiling.is_none() || rate(grid).is_some_and(|rating| ceiling.admits(&rating))
+            };
+            if solver.has_unique_solution(&grid) && within(&grid) {
+                count -= backup.len();
+            } else {
+                for &(row, col, value) in &backup {
+                    grid.set(row, col, value).expect("digit in range");
+                }
+            }
+        }
+        grid
+    }
+
+    /**
+     * Fills the given Sudoku grid with numbers in a randomized order.
+     *
+     * Each cell tries its candidates in a fresh random order. Under the
+     * classic rules the filled grid is then passed through a random symmetry
+     * of the Sudoku rules so that no row, column or digit is favoured by the
+     * search order; other rules need not survive such a shuffle.
+     *
+     * A search that drags on is restarted in a new random order, as some
+     * variants, such as jigsaw layouts, send a few orders into long dead
+     * ends. The placement budgets of the tries follow the Luby sequence, so
+     * most tries are short while some grow long enough to prove that the
+     * rules admit no filled grid.
+     *
+     * @param grid The Sudoku grid to be filled (modified by reference)
+     * @param rules The constraints the filled grid must satisfy.
+     * @return True if the grid was filled.
+     */
+    fn fill(&mut self, grid: &mut Grid, rules: &Rules) -> bool {
+        let layout = rules.layout(grid.shape());
+        let board = (1..)
+            .find_map(|attempt| {
+                let Some(mut board) = Board::with_layout(grid, Arc::clone(&layout)) else {
+                    return Some(None);
+                };
+                let mut left = FILL_BUDGET_PER_CELL * grid.shape().cells() * luby(attempt);
+                if fill_recursive(&mut board, &mut self.rng, &mut left) {
+                    Some(Some(board))
+                } else if left > 0 {
+                    Some(None)
+                } else {
+                    None
+                }
+            })
+            .flatten();
+        let Some(board) = board else {
+            return false;
+        };
+        *grid = board.to_grid();
+        if rules.is_classic() {
+            *grid = self.transform(grid);
+        }
+        true
+    }
+
+    /**
+     * Applies a random validity-preserving transformation to a grid: a
+     * relabeling of the digits, a permutation of the bands and of the rows
+     * within each band, the same for stacks and columns, and a transposition
+     * half of the time when the boxes are square.
+     * @param grid The grid to transform.
+     * @return The transformed grid.
+     */
+    fn transform(&mut self, grid: &Grid) -> Grid {
+        let shape = grid.shape();
+        let rows = self.line_permutation(shape.box_cols(), shape.box_rows());
+        let cols = self.line_permutation(shape.box_rows(), shape.box_cols());
+        let transpose = self.rng.gen_bool(0.5) && shape.box_rows() == shape.box_cols();
+        let mut labels: Vec<u8> = (1..=shape.size() as u8).collect();
+        labels.shuffle(&mut self.rng);
+
+        let mut result = Grid::new(shape);
+        for (row, &source_row) in rows.iter().enumerate() {
+            for (col, &source_col) in cols.iter().enumerate() {
+                let (from_row, from_col) = if transpose {
+                    (source_col, source_row)
+                } else {
+                    (source_row, source_col)
+                };
+                if let Some(value) = grid.get(from_row, from_col) {
+                    let label = labels[value as usize - 1];
+                    result.set(row, col, label).expect("digit in range");
+                }
+            }
+        }
+        result
+    }
+
+    /**
+     * Returns a random permutation of line indices that keeps lines of the
+     * same band together.
+     * @param bands The number of bands.
+     * @param width The number of lines in a band.
+     */
+    fn line_permutation(&mut self, bands: usize, width: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..bands).collect();
+        order.shuffle(&mut self.rng);
+        let mut lines = vec![0; bands * width];
+        for (i, band) in order.into_iter().enumerate() {
+            let mut offsets: Vec<usize> = (0..width).collect();
+            offsets.shuffle(&mut self.rng);
+            for (j, offset) in offsets.into_iter().enumerate() {
+                lines[i * width + j] = band * width + offset;
+            }
+        }
+        lines
+    }
+}
+
+/**
+ * The settings of one clue removal pass.
+ */
+#[derive(Clone, Copy)]
+struct Removal<'a> {
+    /** Checks uniqueness under the puzzle's rules. */
+    solver: &'a Solver,
+    /** The number of filled cells to stop at. */
+    filled: usize,
+    symmetry: Symmetry,
+    ceiling: Ceiling,
+    deadline: Option<Instant>,
+}
+
+impl Default for Generator {
+    fn default() -> Self {
+        Generator::new()
+    }
+}
+
+/**
+ * Rounds of reshaping the boxes into jigsaw regions, each with a new filled
+ * grid of the regions so far.
+ */
+const RESHAPES: usize = 8;
+
+/** Placements per cell of the shortest tries at filling a grid. */
+const FILL_BUDGET_PER_CELL: usize = 4;
+
+/**
+ * Returns the `i`-th term of the Luby sequence 1, 1, 2, 1, 1, 2, 4, 1, ...,
+ * counting from 1.
+ */
+fn luby(mut i: usize) -> usize {
+    loop {
+        let bits = usize::BITS - i.leading_zeros();
+        if i + 1 == 1 << bits {
+            return 1 << (bits - 1);
+        }
+        i -= (1 << (bits - 1)) - 1;
+    }
+}
+
+/**
+ * Recursively fills the board with valid numbers, branching on the most
+ * constrained cell and trying its candidates in random order.
+ * @param board The board to fill.
+ * @param rng The source of the candidate order.
+ * @param budget The placements left; the search gives up when it runs out.
+ * @return True if the board is successfully filled, false otherwise.
+ */
+fn fill_recursive<R: Rng>(board: &mut Board, rng: &mut R, budget: &mut usize) -> bool {
+    let Some((cell, candidates)) = board.most_constrained() else {
+        return true;
+    };
+    let mut numbers: Vec<u8> = digits(candidates).collect();
+    numbers.shuffle(rng);
+    for num in numbers {
+        if *budget == 0 {
+            return false;
+        }
+        *budget -= 1;
+        board.place(cell, num);
+        if fill_recursive(board, rng, budget) {
+            return true;
+        }
+        board.unplace(cell);
+    }
+    false
+}
@@ -0,0 +1,492 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::format::{self, ParseError};
+
+/** Height and width of a box of the classic grid. */
+pub const BOX_SIZE: usize = 3;
+
+/** Number of rows, columns and digits of the classic grid. */
+pub const SIZE: usize = BOX_SIZE * BOX_SIZE;
+
+/** Number of cells of the classic grid. */
+pub const CELLS: usize = SIZE * SIZE;
+
+/**
+ * Errors raised when a value would break the invariants of a `Grid`.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /** A cell value outside `1..=size` (or `0..=size` where blanks are allowed). */
+    OutOfRange { row: usize, col: usize, value: u8 },
+    /** The input did not contain exactly the cells of a grid. */
+    WrongLength { expected: usize, found: usize },
+    /** Box dimensions that do not make a supported grid. */
+    InvalidShape { box_rows: usize, box_cols: usize },
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::OutOfRange { row, col, value } => write!(
+                f,
+                "value {} at row {}, column {} is out of range",
+                value,
+                row + 1,
+                col + 1,
+            ),
+            GridError::WrongLength { expected, found } => {
+                write!(f, "expected {} cells, found {}", expected, found)
+            }
+            GridError::InvalidShape { box_rows, box_cols } => write!(
+                f,
+                "{}x{} boxes do not make a grid of 4 to {} digits",
+                box_rows,
+                box_cols,
+                Shape::MAX_SIZE
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GridError {}
+
+/**
+ * The dimensions of a grid, given by the height and width of its boxes.
+ *
+ * A grid with `r`x`c` boxes has `r * c` rows, columns and digits. Boxes
+ * may be rectangular, as the 2x3 boxes of a 6x6 grid are.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Shape {
+    box_rows: usize,
+    box_cols: usize,
+}
+
+impl Shape {
+    /** The classic 9x9 grid with 3x3 boxes. */
+    pub const CLASSIC: Shape = Shape {
+        box_rows: BOX_SIZE,
+        box_cols: BOX_SIZE,
+    };
+
+    /** The largest number of digits, as digit sets are 32-bit masks. */
+    pub const MAX_SIZE: usize = 25;
+
+    /**
+     * Creates a shape from box dimensions.
+     * @param box_rows The height of a box, at least 2.
+     * @param box_cols The width of a box, at least 2.
+     * @return The shape, or an error if a side is below 2 or the grid would
+     * have more than `MAX_SIZE` digits.
+     */
+    pub fn new(box_rows: usize, box_cols: usize) -> Result<Self, GridError> {
+        if box_rows < 2 || box_cols < 2 || box_rows * box_cols > Shape::MAX_SIZE {
+            return Err(GridError::InvalidShape { box_rows, box_cols });
+        }
+        Ok(Shape { box_rows, box_cols })
+    }
+
+    /**
+     * Returns the usual shape for a number of digits: the boxes closest to
+     * square, no taller than they are wide, so 6 gives 2x3 and 12 gives 3x4.
+     * @return The shape, or `None` if no supported boxes have that size.
+     */
+    pub fn for_size(size: usize) -> Option<Self> {
+        (2..=size)
+            .take_while(|rows| rows * rows <= size)
+            .filter(|rows| size.is_multiple_of(*rows))
+            .last()
+            .and_then(|rows| Shape::new(rows, size / rows).ok())
+    }
+
+    /**
+     * Returns the usual shape for a number of cells, as `for_size` does.
+     */
+    pub fn for_cells(cells: usize) -> Option<Self> {
+        let size = (1..=Shape::MAX_SIZE).find(|size| size * size >= cells)?;
+        if size * size == cells {
+            Shape::for_size(size)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Returns every supported number of cells, smallest first.
+     */
+    pub(crate) fn cell_counts() -> impl Iterator<Item = usize> {
+        (4..=Shape::MAX_SIZE)
+            .filter(|&size| Shape::for_size(size).is_some())
+            .map(|size| size * size)
+    }
+
+    /** Returns the height of a box. */
+    pub fn box_rows(self) -> usize {
+        self.box_rows
+    }
+
+    /** Returns the width of a box. */
+    pub fn box_cols(self) -> usize {
+        self.box_cols
+    }
+
+    /** Returns the number of rows, columns, boxes and digits. */
+    pub fn size(self) -> usize {
+        self.box_rows * self.box_cols
+    }
+
+    /** Returns the number of cells. */
+    pub fn cells(self) -> usize {
+        self.size() * self.size()
+    }
+
+    /**
+     * Returns the box that contains a cell, numbered in row-major order.
+     */
+    pub fn box_of(self, row: usize, col: usize) -> usize {
+        (row / self.box_rows) * (self.size() / self.box_cols) + col / self.box_cols
+    }
+}
+
+impl Default for Shape {
+    fn default() -> Self {
+        Shape::CLASSIC
+    }
+}
+
+/**
+ * Writes the box dimensions, such as `2x3`.
+ */
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.box_rows, self.box_cols)
+    }
+}
+
+/**
+ * Reads box dimensions such as `2x3`, or a number of digits such as `16`
+ * for its usual shape.
+ */
+impl FromStr for Shape {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid box shape '{}'", s);
+        match s.split_once(['x', 'X']) {
+            Some((rows, cols)) => {
+                let rows = rows.trim().parse().map_err(|_| invalid())?;
+                let cols = cols.trim().parse().map_err(|_| invalid())?;
+                Shape::new(rows, cols).map_err(|error| error.to_string())
+            }
+            None => s
+                .trim()
+                .parse()
+                .ok()
+                .and_then(Shape::for_size)
+                .ok_or_else(invalid),
+        }
+    }
+}
+
+/**
+ * Returns the character for a digit: `1`-`9`, then `A` for 10 through `P`
+ * for 25, and `0` for a blank.
+ */
+pub(crate) fn symbol(digit: u8) -> char {
+    match digit {
+        0..=9 => char::from(b'0' + digit),
+        _ => char::from(b'A' + digit - 10),
+    }
+}
+
+/**
+ * Reads a digit character written by `symbol`, in either case.
+ * @return The digit, `0` for `0`, or `None` for any other character.
+ */
+pub(crate) fn symbol_value(c: char) -> Option<u8> {
+    match c {
+        '0'..='9' => Some(c as u8 - b'0'),
+        'A'..='Z' => Some(c as u8 - b'A' + 10),
+        'a'..='z' => Some(c as u8 - b'a' + 10),
+        _ => None,
+    }
+}
+
+/**
+ * Iterates over the cells above, below, left and right of a cell.
+ * @param cell The row-major index of the cell.
+ * @param size The number of cells on a side of the grid.
+ */
+pub(crate) fn neighbours(cell: usize, size: usize) -> impl Iterator<Item = usize> {
+    let (row, col) = (cell / size, cell % size);
+    [
+        (row > 0).then(|| cell - size),
+        (row + 1 < size).then(|| cell + size),
+        (col > 0).then(|| cell - 1),
+        (col + 1 < size).then(|| cell + 1),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+/**
+ * A Sudoku grid of any supported shape, 9x9 unless built otherwise.
+ *
+ * Each cell is either blank or holds a digit in `1..=size`. The grid only
+ * guarantees the value range; it does not reject placements that break the
+ * Sudoku rules, use `is_safe` for that.
+ */
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Grid {
+    shape: Shape,
+    /** Row-major digits, `0` for blanks. */
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    /**
+     * Creates a classic 9x9 grid with every cell blank.
+     */
+    pub fn empty() -> Self {
+        Grid::new(Shape::CLASSIC)
+    }
+
+    /**
+     * Creates a grid of a shape with every cell blank.
+     */
+    pub fn new(shape: Shape) -> Self {
+        Grid {
+            shape,
+            cells: vec![0; shape.cells()],
+        }
+    }
+
+    /**
+     * Builds a grid from row-major values where `0` marks a blank, in the
+     * usual shape for the number of values.
+     * @param values The cells of a supported grid, such as 81 for 9x9.
+     * @return The grid, or the first invariant that the input breaks.
+     */
+    pub fn from_values(values: &[u8]) -> Result<Self, GridError> {
+        let shape = Shape::for_cells(values.len()).ok_or_else(|| GridError::WrongLength {
+            expected: nearest_cell_count(values.len()),
+            found: values.len(),
+        })?;
+        Grid::with_values(shape, values)
+    }
+
+    /**
+     * Builds a grid of a given shape from row-major values where `0` marks
+     * a blank.
+     * @param values Exactly `shape.cells()` values in `0..=shape.size()`.
+     * @return The grid, or the first invariant that the input breaks.
+     */
+    pub fn with_values(shape: Shape, values: &[u8]) -> Result<Self, GridError> {
+        if values.len() != shape.cells() {
+            return Err(GridError::WrongLength {
+                expected: shape.cells(),
+                found: values.len(),
+            });
+        }
+        let size = shape.size();
+        if let Some(i) = values.iter().position(|&value| usize::from(value) > size) {
+            return Err(GridError::OutOfRange {
+                row: i / size,
+                col: i % size,
+                value: values[i],
+            });
+        }
+        Ok(Grid {
+            shape,
+            cells: values.to_vec(),
+        })
+    }
+
+    /**
+     * Builds a classic grid from rows where `0` marks a blank.
+     * @param rows The nine rows of the grid.
+     * @return The grid, or the first out-of-range value.
+     */
+    pub fn from_rows(rows: &[[u8; SIZE]; SIZE]) -> Result<Self, GridError> {
+        Grid::with_values(Shape::CLASSIC, rows.as_flattened())
+    }
+
+    /**
+     * Returns the shape of the grid.
+     */
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    /**
+     * Returns the number of rows, columns and digits.
+     */
+    pub fn size(&self) -> usize {
+        self.shape.size()
+    }
+
+    /**
+     * Returns the row-major values of the grid, with `0` for blanks.
+     */
+    pub fn to_values(&self) -> Vec<u8> {
+        self.cells.clone()
+    }
+
+    /**
+     * Returns the digit in a cell, or `None` if it is blank.
+     * @param row The row index, panics if not below the size.
+     * @param col The column index, panics if not below the size.
+     */
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        match self.cells[self.index(row, col)] {
+            0 => None,
+            value => Some(value),
+        }
+    }
+
+    /**
+     * Places a digit in a cell.
+     * @param row The row index, panics if not below the size.
+     * @param col The column index, panics if not below the size.
+     * @param value The digit to place, must be in `1..=size`.
+     * @return An error if the digit is out of range; the grid is unchanged.
+     */
+    pub fn set(&mut self, row: usize, col: usize, value: u8) -> Result<(), GridError> {
+        let i = self.index(row, col);
+        if value == 0 || usize::from(value) > self.size() {
+            return Err(GridError::OutOfRange { row, col, value });
+        }
+        self.cells[i] = value;
+        Ok(())
+    }
+
+    /**
+     * Blanks a cell.
+     * @param row The row index, panics if not below the size.
+     * @param col The column index, panics if not below the size.
+     */
+    pub fn clear(&mut self, row: usize, col: usize) {
+        let i = self.index(row, col);
+        self.cells[i] = 0;
+    }
+
+    /**
+     * Checks if it's safe to place a number in a given cell, i.e. the number
+     * is not already used in the cell's row, column or sub-box.
+     * @param row The row index, panics if not below the size.
+     * @param col The column index, panics if not below the size.
+     * @param num The number to check.
+     * @return True if it's safe to place the number, false otherwise or if
+     * the number is outside `1..=size`.
+     */
+    pub fn is_safe(&self, row: usize, col: usize, num: u8) -> bool {
+        if num == 0 || usize::from(num) > self.size() {
+            return false;
+        }
+        let (box_rows, box_cols) = (self.shape.box_rows, self.shape.box_cols);
+        let (box_row, box_col) = (row - row % box_rows, col - col % box_cols);
+        (0..self.size()).all(|i| {
+            self.get(row, i) != Some(num)
+                && self.get(i, col) != Some(num)
+                && self.get(box_row + i / box_cols, box_col + i % box_cols) != Some(num)
+        })
+    }
+
+    /**
+     * Finds the first blank cell in row-major order.
+     * @return The (row, column) of the blank cell, or `None` if the grid is full.
+     */
+    pub fn find_empty(&self) -> Option<(usize, usize)> {
+        let size = self.size();
+        self.cells
+            .iter()
+            .position(|&value| value == 0)
+            .map(|i| (i / size, i % size))
+    }
+
+    /**
+     * Returns the number of filled cells.
+     */
+    pub fn filled_count(&self) -> usize {
+        self.cells.iter().filter(|&&value| value != 0).count()
+    }
+
+    /**
+     * Returns true if no cell is blank.
+     */
+    pub fn is_complete(&self) -> bool {
+        self.cells.iter().all(|&value| value != 0)
+    }
+
+    fn index(&self, row: usize, col: usize) -> usize {
+        let size = self.size();
+        assert!(
+            row < size && col < size,
+            "cell ({}, {}) is outside the grid",
+            row,
+            col
+        );
+        row * size + col
+    }
+}
+
+/**
+ * Returns the supported number of cells closest to `found`, the smaller one
+ * on a tie.
+ */
+pub(crate) fn nearest_cell_count(found: usize) -> usize {
+    Shape::cell_counts()
+        .min_by_key(|&cells| cells.abs_diff(found))
+        .expect("some shapes are supported")
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Grid::empty()
+    }
+}
+
+/**
+ * Writes the grid as one line per row of space-separated digits, `0` for
+ * blanks and letters for digits above 9.
+ */
+impl fmt::Display for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.cells.chunks(self.size()) {
+            for (col, &value) in row.iter().enumerate() {
+                if col > 0 {
+                    f.write_str(" ")?;
+                }
+                write!(f, "{}", symbol(value))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Reads a grid in any of the formats `format::parse` accepts.
+ */
+impl FromStr for Grid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        format::parse(s)
+    }
+}
+
+impl fmt::Debug for Grid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Grid(")?;
+        if self.shape != Shape::CLASSIC {
+            write!(f, "{}, ", self.shape)?;
+        }
+        write!(f, "\"")?;
+        for &value in &self.cells {
+            write!(f, "{}", symbol(value))?;
+        }
+        write!(f, "\")")
+    }
+}
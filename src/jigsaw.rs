@@ -0,0 +1,304 @@
+/*!
+ * Jigsaw Sudoku: irregular regions of connected cells take the place of the
+ * boxes, and random region layouts for generating such puzzles.
+ */
+
+use std::fmt;
+use std::str::FromStr;
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::constraint::Constraint;
+use crate::grid::{nearest_cell_count, neighbours, symbol, symbol_value, Grid, Shape};
+
+/**
+ * Errors raised when a region map does not make a jigsaw layout.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionError {
+    /** The map did not hold exactly one region per cell of a grid. */
+    WrongLength { expected: usize, found: usize },
+    /** A character that is not a region symbol. */
+    InvalidSymbol(char),
+    /** A cell assigned to a region past the last one. */
+    RegionOutOfRange { cell: usize, region: usize },
+    /** A region with more or fewer cells than there are digits. */
+    WrongSize { region: usize, cells: usize },
+    /** A region whose cells do not all touch one another side by side. */
+    Disconnected(usize),
+}
+
+impl fmt::Display for RegionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegionError::WrongLength { expected, found } => {
+                write!(f, "expected {} cells, found {}", expected, found)
+            }
+            RegionError::InvalidSymbol(c) => write!(f, "'{}' is not a region", c),
+            RegionError::RegionOutOfRange { cell, region } => {
+                write!(
+                    f,
+                    "cell {} is in region {}, past the last one",
+                    cell,
+                    region + 1
+                )
+            }
+            RegionError::WrongSize { region, cells } => write!(
+                f,
+                "region {} has {} cells instead of one per digit",
+                region + 1,
+                cells
+            ),
+            RegionError::Disconnected(region) => {
+                write!(f, "region {} is not connected", region + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegionError {}
+
+/**
+ * The regions of a Jigsaw Sudoku, as a constraint: each region is a
+ * connected group of as many cells as there are digits, and holds each
+ * digit once.
+ *
+ * Together with `Rows` and `Columns` the regions replace the classic boxes,
+ * as in `Rules::jigsaw`. They apply to grids of their size only, whatever
+ * the box shape those grids are read with.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Regions {
+    shape: Shape,
+    /** The index of the region of each cell. */
+    region_of: Vec<usize>,
+    /** The cells of each region, in ascending order. */
+    cells: Vec<Vec<usize>>,
+}
+
+impl Regions {
+    /**
+     * Checks a region map against a grid shape.
+     * @param shape The shape of the grids the regions apply to.
+     * @param map The region of each cell in row-major order, from `0` to
+     * one less than the size of the grid.
+     * @return The regions, or the first problem found.
+     */
+    pub fn new(shape: Shape, map: &[usize]) -> Result<Self, RegionError> {
+        let size = shape.size();
+        if map.len() != shape.cells() {
+            return Err(RegionError::WrongLength {
+                expected: shape.cells(),
+                found: map.len(),
+            });
+        }
+        let mut cells = vec![Vec::with_capacity(size); size];
+        for (cell, &region) in map.iter().enumerate() {
+            match cells.get_mut(region) {
+                Some(members) => members.push(cell),
+                None => return Err(RegionError::RegionOutOfRange { cell, region }),
+            }
+        }
+        for (region, members) in cells.iter().enumerate() {
+            if members.len() != size {
+                return Err(RegionError::WrongSize {
+                    region,
+                    cells: members.len(),
+                });
+            }
+            if !is_connected(map, size, region, members[0]) {
+                return Err(RegionError::Disconnected(region));
+            }
+        }
+        Ok(Regions {
+            shape,
+            region_of: map.to_vec(),
+            cells,
+        })
+    }
+
+    /**
+     * Creates the regions of a shape's boxes, numbered like `Shape::box_of`.
+     */
+    pub fn boxes(shape: Shape) -> Self {
+        let size = shape.size();
+        let map: Vec<usize> = (0..shape.cells())
+            .map(|cell| shape.box_of(cell / size, cell % size))
+            .collect();
+        Regions::new(shape, &map).expect("boxes are regions")
+    }
+
+    /**
+     * Returns the shape the regions were made for.
+     */
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    /**
+     * Returns the index of the region that contains a cell.
+     */
+    pub fn region_of(&self, row: usize, col: usize) -> usize {
+        self.region_of[row * self.shape.size() + col]
+    }
+
+    /**
+     * Returns the cells of a region in ascending row-major order.
+     */
+    pub fn cells_of(&self, region: usize) -> &[usize] {
+        &self.cells[region]
+    }
+}
+
+impl Constraint for Regions {
+    fn name(&self) -> &str {
+        "regions"
+    }
+
+    fn regions(&self, shape: Shape) -> Vec<Vec<usize>> {
+        if shape.size() != self.shape.size() {
+            return Vec::new();
+        }
+        self.cells.clone()
+    }
+}
+
+/**
+ * Writes the region map one row per line, each region as the symbol of the
+ * digit one above its index: `1` for the first region, `A` for the tenth.
+ */
+impl fmt::Display for Regions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.region_of.chunks(self.shape.size()) {
+            let line: String = row.iter().map(|&region| symbol(region as u8 + 1)).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Reads a region map as written by `Display`, ignoring whitespace, so it may
+ * also be a single line. The shape is the usual one for the number of cells.
+ */
+impl FromStr for Regions {
+    type Err = RegionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let map = s
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| match symbol_value(c) {
+                Some(value) if value > 0 => Ok(usize::from(value) - 1),
+                _ => Err(RegionError::InvalidSymbol(c)),
+            })
+            .collect::<Result<Vec<usize>, RegionError>>()?;
+        let shape = Shape::for_cells(map.len()).ok_or(RegionError::WrongLength {
+            expected: nearest_cell_count(map.len()),
+            found: map.len(),
+        })?;
+        Regions::new(shape, &map)
+    }
+}
+
+/** Swaps tried per cell in each call of `reshape`. */
+const SWAPS_PER_CELL: usize = 32;
+
+/**
+ * Reshapes regions at random into others that a filled grid still fits.
+ *
+ * A cell on the boundary of its region trades regions with the cell of the
+ * neighbouring region that holds the same digit, which keeps each digit once
+ * in both. A swap is kept when that other cell touches its new region and
+ * both regions stay connected without lying along a single row or column,
+ * which would only repeat a rule. As the grid pins the digits down, only so
+ * many swaps are open with one grid; callers reshape again with a fresh
+ * grid to mix the layout further.
+ *
+ * @param rng The source of the swaps.
+ * @param regions The regions to start from.
+ * @param solution A filled grid that fits the regions.
+ * @return The new regions, which the grid fits as well.
+ */
+pub(crate) fn reshape<R: Rng>(rng: &mut R, regions: &Regions, solution: &Grid) -> Regions {
+    let shape = regions.shape();
+    let size = shape.size();
+    let cells = shape.cells();
+    let values = solution.to_values();
+    let mut map = regions.region_of.clone();
+    // The cell of each region holding each digit, at `region * size + digit - 1`.
+    let mut holder = vec![0; cells];
+    for (cell, &region) in map.iter().enumerate() {
+        holder[region * size + usize::from(values[cell]) - 1] = cell;
+    }
+    for _ in 0..cells * SWAPS_PER_CELL {
+        let cell = rng.gen_range(0..cells);
+        let from = map[cell];
+        let outside: Vec<usize> = neighbours(cell, size)
+            .filter(|&other| map[other] != from)
+            .collect();
+        let Some(&other) = outside.choose(rng) else {
+            continue;
+        };
+        let to = map[other];
+        let digit = usize::from(values[cell]) - 1;
+        let swap = holder[to * size + digit];
+        map[cell] = to;
+        map[swap] = from;
+        let kept = neighbours(swap, size).any(|next| map[next] == from && next != cell)
+            && is_connected(&map, size, from, swap)
+            && is_connected(&map, size, to, cell)
+            && !is_straight(&map, size, from)
+            && !is_straight(&map, size, to);
+        if kept {
+            holder[to * size + digit] = cell;
+            holder[from * size + digit] = swap;
+        } else {
+            map[cell] = from;
+            map[swap] = to;
+        }
+    }
+    Regions::new(shape, &map).expect("swaps keep regions whole")
+}
+
+/**
+ * Returns true if every cell of a region can be reached from `start` through
+ * side-by-side neighbours in the region.
+ * @param map The region of each cell.
+ * @param size The number of cells on a side of the grid.
+ * @param region The region to check.
+ * @param start A cell of the region.
+ */
+fn is_connected(map: &[usize], size: usize, region: usize, start: usize) -> bool {
+    let mut seen = vec![false; map.len()];
+    seen[start] = true;
+    let mut stack = vec![start];
+    let mut reached = 0;
+    while let Some(cell) = stack.pop() {
+        reached += 1;
+        for next in neighbours(cell, size) {
+            if map[next] == region && !seen[next] {
+                seen[next] = true;
+                stack.push(next);
+            }
+        }
+    }
+    reached == map.iter().filter(|&&other| other == region).count()
+}
+
+/**
+ * Returns true if every cell of a region lies in one row or one column.
+ */
+fn is_straight(map: &[usize], size: usize, region: usize) -> bool {
+    let mut cells = (0..map.len()).filter(|&cell| map[cell] == region);
+    let Some(first) = cells.next() else {
+        return false;
+    };
+    let (mut row, mut col) = (true, true);
+    for cell in cells {
+        row &= cell / size == first / size;
+        col &= cell % size == first % size;
+    }
+    row || col
+}
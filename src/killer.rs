@@ -0,0 +1,862 @@
+/*!
+ * Killer Sudoku: cages of cells whose digits differ and add up to a given
+ * sum, and the generation of caged puzzles with few or no givens.
+ */
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::board::{bit, digits, Mask};
+use crate::constraint::{Constraint, Rules};
+use crate::grid::{neighbours, Grid, Shape};
+use crate::solver::Solver;
+
+/**
+ * Errors raised when cages do not fit a grid.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CageError {
+    /** A cell index past the last cell of the grid. */
+    CellOutOfRange(usize),
+    /** A cell that belongs to more than one cage. */
+    SharedCell(usize),
+    /** A cage whose sum no set of distinct digits can make. */
+    ImpossibleSum { cage: usize, sum: u32 },
+    /** A line that is not a sum, a colon and cells. */
+    InvalidCage(String),
+    /** A cell that is not written as `r1c1` within the largest grid. */
+    InvalidCell(String),
+    /** Text without any cage. */
+    Empty,
+}
+
+impl fmt::Display for CageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CageError::CellOutOfRange(cell) => write!(f, "cell {} is outside the grid", cell),
+            CageError::SharedCell(cell) => write!(f, "cell {} is in two cages", cell),
+            CageError::ImpossibleSum { cage, sum } => write!(
+                f,
+                "cage {} cannot add up to {} with distinct digits",
+                cage + 1,
+                sum
+            ),
+            CageError::InvalidCage(line) => {
+                write!(f, "'{}' is not a cage, such as '12: r1c1 r1c2'", line)
+            }
+            CageError::InvalidCell(cell) => write!(f, "'{}' is not a cell", cell),
+            CageError::Empty => f.write_str("no cages were given"),
+        }
+    }
+}
+
+impl std::error::Error for CageError {}
+
+/**
+ * A group of cells whose digits all differ and add up to `sum`.
+ *
+ * Cells are numbered in row-major order, as in `Constraint`.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cage {
+    sum: u32,
+    cells: Vec<usize>,
+}
+
+impl Cage {
+    /**
+     * Creates a cage; its cells are kept in ascending order.
+     * @param sum The total of the cage's digits.
+     * @param cells The row-major indices of its cells.
+     */
+    pub fn new(sum: u32, cells: &[usize]) -> Self {
+        let mut cells = cells.to_vec();
+        cells.sort_unstable();
+        cells.dedup();
+        Cage { sum, cells }
+    }
+
+    /**
+     * Returns the total of the cage's digits.
+     */
+    pub fn sum(&self) -> u32 {
+        self.sum
+    }
+
+    /**
+     * Returns the cells of the cage in ascending order; the first is the
+     * top-left one, where the sum is printed.
+     */
+    pub fn cells(&self) -> &[usize] {
+        &self.cells
+    }
+}
+
+/**
+ * The cages of a Killer Sudoku, as a constraint.
+ *
+ * Each cage is a region, so its digits differ, and candidates are pruned
+ * to the digits that appear in some combination of distinct digits making
+ * up what is left of the cage's sum. Cages need not cover the grid.
+ *
+ * Within rules, the search also prunes by the houses of those rules: the
+ * groups of cells that hold every digit once, such as the rows, columns and
+ * boxes of the classic rules or the regions of a jigsaw. The cells of a
+ * house outside the cages it contains add up to the rest of its total.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cages {
+    shape: Shape,
+    cages: Vec<Cage>,
+    /** The index of the cage of each cell, if any. */
+    cage_of: Vec<Option<usize>>,
+    /**
+     * The sums the search checks: every cage, and once adapted to rules by
+     * `within`, for each house the cells left over by the cages inside it.
+     */
+    sums: Vec<Cage>,
+    /** The indices of the sums that cover each cell. */
+    sums_of: Vec<Vec<usize>>,
+}
+
+impl Cages {
+    /**
+     * Checks cages against a grid shape.
+     * @param shape The shape of the grids the cages apply to.
+     * @param cages The cages, which must not overlap.
+     * @return The constraint, or the first problem found.
+     */
+    pub fn new(shape: Shape, cages: Vec<Cage>) -> Result<Self, CageError> {
+        let all = (1 << shape.size()) - 1;
+        let mut cage_of = vec![None; shape.cells()];
+        for (index, cage) in cages.iter().enumerate() {
+            for &cell in &cage.cells {
+                match cage_of.get_mut(cell) {
+                    None => return Err(CageError::CellOutOfRange(cell)),
+                    Some(Some(_)) => return Err(CageError::SharedCell(cell)),
+                    Some(slot) => *slot = Some(index),
+                }
+            }
+            if cage.cells.is_empty()
+                || combinations(shape.size(), all, cage.cells.len(), cage.sum).is_none()
+            {
+                return Err(CageError::ImpossibleSum {
+                    cage: index,
+                    sum: cage.sum,
+                });
+            }
+        }
+        let mut sums_of = vec![Vec::new(); shape.cells()];
+        for (index, cage) in cages.iter().enumerate() {
+            for &cell in &cage.cells {
+                sums_of[cell].push(index);
+            }
+        }
+        Ok(Cages {
+            shape,
+            sums: cages.clone(),
+            cages,
+            cage_of,
+            sums_of,
+        })
+    }
+
+    /**
+     * Adds the sum of the cells of a house left over by the cages inside it.
+     * @param house Cells that hold every digit once.
+     */
+    fn add_house(&mut self, house: &[usize]) {
+        let total = total_of(self.shape.size());
+        let mut inside = 0;
+        let mut rest = Vec::new();
+        for &cell in house {
+            match self.cage_of[cell].map(|index| &self.cages[index]) {
+                Some(cage) if cage.cells.iter().all(|member| house.contains(member)) => {
+                    if cage.cells[0] == cell {
+                        inside += cage.sum;
+                    }
+                }
+                _ => rest.push(cell),
+            }
+        }
+        if rest.is_empty() || rest.len() == house.len() {
+            return;
+        }
+        if let Some(sum) = total.checked_sub(inside) {
+            for &cell in &rest {
+                self.sums_of[cell].push(self.sums.len());
+            }
+            self.sums.push(Cage { sum, cells: rest });
+        }
+    }
+
+    /**
+     * Cages a filled grid, taking each cage's sum from its digits.
+     * @param solution The filled grid.
+     * @param groups The cells of each cage.
+     */
+    fn from_solution(solution: &Grid, groups: &[Vec<usize>]) -> Self {
+        let values = solution.to_values();
+        let mut cages: Vec<Cage> = groups
+            .iter()
+            .map(|cells| {
+                let sum = cells.iter().map(|&cell| u32::from(values[cell])).sum();
+                Cage::new(sum, cells)
+            })
+            .collect();
+        cages.sort_by_key(|cage| cage.cells[0]);
+        Cages::new(solution.shape(), cages).expect("cages of a solution fit it")
+    }
+
+    /**
+     * Returns the shape of the grids the cages apply to.
+     */
+    pub fn shape(&self) -> Shape {
+        self.shape
+    }
+
+    /**
+     * Returns the cages, ordered by their first cell.
+     */
+    pub fn cages(&self) -> &[Cage] {
+        &self.cages
+    }
+
+    /**
+     * Returns the cage that contains a cell, if any.
+     */
+    pub fn cage_of(&self, row: usize, col: usize) -> Option<&Cage> {
+        let index = (*self.cage_of.get(row * self.shape.size() + col)?)?;
+        Some(&self.cages[index])
+    }
+}
+
+impl Constraint for Cages {
+    fn name(&self) -> &str {
+        "cages"
+    }
+
+    fn regions(&self, shape: Shape) -> Vec<Vec<usize>> {
+        if shape.size() != self.shape.size() {
+            return Vec::new();
+        }
+        self.cages.iter().map(|cage| cage.cells.clone()).collect()
+    }
+
+    fn eliminates(&self) -> bool {
+        true
+    }
+
+    fn eliminate(&self, values: &[u8], cell: usize, candidates: u32) -> u32 {
+        if values.len() != self.shape.cells() {
+            return candidates;
+        }
+        let sums = &self.sums_of[cell];
+        let size = self.shape.size();
+        let all = (1 << size) - 1;
+        let mut candidates = candidates;
+        for &index in sums {
+            let cage = &self.sums[index];
+            let (mut placed, mut used, mut blanks) = (0, 0, 0);
+            for &member in &cage.cells {
+                match values[member] {
+                    0 => blanks += 1,
+                    value => {
+                        placed += u32::from(value);
+                        used |= bit(value);
+                    }
+                }
+            }
+            candidates &= match cage.sum.checked_sub(placed) {
+                Some(left) => combinations(size, all & !used, blanks, left).unwrap_or(0),
+                None => 0,
+            };
+            if candidates == 0 {
+                break;
+            }
+        }
+        candidates
+    }
+
+    /**
+     * Adds the sums of the houses among the units: those with a cell per
+     * digit, which hold every digit once.
+     */
+    fn within(&self, shape: Shape, units: &[Vec<usize>]) -> Option<Arc<dyn Constraint>> {
+        if shape.size() != self.shape.size() {
+            return None;
+        }
+        let mut cages = self.clone();
+        for house in units.iter().filter(|unit| unit.len() == shape.size()) {
+            cages.add_house(house);
+        }
+        Some(Arc::new(cages))
+    }
+
+    /**
+     * Checks that no digit repeats in a cage, that full cages add up to
+     * their sum and that no partial cage has gone past it.
+     */
+    fn is_satisfied(&self, grid: &Grid) -> bool {
+        if grid.size() != self.shape.size() {
+            return true;
+        }
+        let values = grid.to_values();
+        self.cages.iter().all(|cage| {
+            let (mut placed, mut used, mut blanks) = (0, 0, 0);
+            for &cell in &cage.cells {
+                match values[cell] {
+                    0 => blanks += 1,
+                    value if used & bit(value) != 0 => return false,
+                    value => {
+                        placed += u32::from(value);
+                        used |= bit(value);
+                    }
+                }
+            }
+            match blanks {
+                0 => placed == cage.sum,
+                _ => placed + blanks <= cage.sum,
+            }
+        })
+    }
+}
+
+impl fmt::Display for Cages {
+    /**
+     * Writes one cage per line: its sum, a colon and its cells as `r1c1`.
+     */
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let size = self.shape.size();
+        for cage in &self.cages {
+            write!(f, "{}:", cage.sum)?;
+            for &cell in &cage.cells {
+                write!(f, " r{}c{}", cell / size + 1, cell % size + 1)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Reads cages as written by `Display`, one per line or separated by `;`,
+ * skipping blank ones. The shape is the usual one for the largest row or
+ * column named, so cages that cover their grid, as generated ones do, get
+ * its size back.
+ */
+impl FromStr for Cages {
+    type Err = CageError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parsed = Vec::new();
+        let lines = s.split(['\n', ';']).map(str::trim);
+        for line in lines.filter(|line| !line.is_empty()) {
+            let invalid = || CageError::InvalidCage(line.to_string());
+            let (sum, cells) = line.split_once(':').ok_or_else(invalid)?;
+            let sum: u32 = sum.trim().parse().map_err(|_| invalid())?;
+            let cells = cells
+                .split_whitespace()
+                .map(|cell| position(cell).ok_or_else(|| CageError::InvalidCell(cell.to_string())))
+                .collect::<Result<Vec<_>, _>>()?;
+            if cells.is_empty() {
+                return Err(invalid());
+            }
+            parsed.push((sum, cells));
+        }
+        let largest = parsed
+            .iter()
+            .flat_map(|(_, cells)| cells)
+            .map(|&(row, col)| row.max(col) + 1)
+            .max()
+            .ok_or(CageError::Empty)?;
+        let shape = (largest..=Shape::MAX_SIZE)
+            .find_map(Shape::for_size)
+            .expect("the largest grid has a shape");
+        let cages = parsed
+            .iter()
+            .map(|(sum, cells)| {
+                let cells: Vec<usize> = cells
+                    .iter()
+                    .map(|&(row, col)| row * shape.size() + col)
+                    .collect();
+                Cage::new(*sum, &cells)
+            })
+            .collect();
+        Cages::new(shape, cages)
+    }
+}
+
+/**
+ * Reads a cell written as `r1c1`, in either case.
+ * @return The zero-based row and column, or `None` if the text is not a
+ * cell of the largest grid.
+ */
+fn position(text: &str) -> Option<(usize, usize)> {
+    let text = text.to_ascii_lowercase();
+    let (row, col) = text.strip_prefix('r')?.split_once('c')?;
+    let (row, col): (usize, usize) = (row.parse().ok()?, col.parse().ok()?);
+    let range = 1..=Shape::MAX_SIZE;
+    (range.contains(&row) && range.contains(&col)).then(|| (row - 1, col - 1))
+}
+
+/** Grids up to this size look combinations up in a table. */
+const TABLE_SIZE: usize = 16;
+
+static TABLES: [OnceLock<Vec<Vec<Mask>>>; TABLE_SIZE + 1] =
+    [const { OnceLock::new() }; TABLE_SIZE + 1];
+
+/**
+ * Returns every set of distinct digits up to `size`, grouped by how many
+ * digits the set has and their total, at `count * (total_of(size) + 1) + sum`.
+ */
+fn table(size: usize) -> &'static [Vec<Mask>] {
+    TABLES[size].get_or_init(|| {
+        let width = total_of(size) as usize + 1;
+        let mut table = vec![Vec::new(); (size + 1) * width];
+        for mask in 0..1 << size {
+            let sum: usize = digits(mask).map(usize::from).sum();
+            table[mask.count_ones() as usize * width + sum].push(mask);
+        }
+        table
+    })
+}
+
+/**
+ * Returns the total of the digits `1..=size`.
+ */
+fn total_of(size: usize) -> u32 {
+    (size * (size + 1) / 2) as u32
+}
+
+/**
+ * Finds the digits that take part in some set of `count` distinct digits
+ * from `free` adding up to `sum`.
+ * @param size The largest digit.
+ * @return The union of every such set, or `None` if there is none.
+ */
+fn combinations(size: usize, free: Mask, count: usize, sum: u32) -> Option<Mask> {
+    if count > size || sum > total_of(size) {
+        return None;
+    }
+    if size > TABLE_SIZE {
+        return search(free, count, sum);
+    }
+    let width = total_of(size) as usize + 1;
+    table(size)[count * width + sum as usize]
+        .iter()
+        .filter(|&&set| set & !free == 0)
+        .fold(None, |union, &set| Some(union.unwrap_or(0) | set))
+}
+
+/**
+ * Finds the digits of `combinations` by a depth-first search, for grids too
+ * large for a table.
+ */
+fn search(free: Mask, count: usize, sum: u32) -> Option<Mask> {
+    if count == 0 {
+        return (sum == 0).then_some(0);
+    }
+    if largest(free, count) < sum {
+        return None;
+    }
+    let mut union = None;
+    let mut rest = free;
+    while rest != 0 {
+        let low = rest & rest.wrapping_neg();
+        rest &= rest - 1;
+        let digit = low.trailing_zeros() + 1;
+        // The other digits are all larger, so the smallest total is above
+        // `digit * count`.
+        if digit * count as u32 > sum {
+            break;
+        }
+        if let Some(more) = search(rest, count - 1, sum - digit) {
+            union = Some(union.unwrap_or(0) | more | low);
+        }
+    }
+    union
+}
+
+/**
+ * Returns the total of the `count` largest digits in a mask, or less if it
+ * has fewer.
+ */
+fn largest(mut mask: Mask, count: usize) -> u32 {
+    let mut total = 0;
+    for _ in 0..count {
+        if mask == 0 {
+            break;
+        }
+        let digit = 32 - mask.leading_zeros();
+        total += digit;
+        mask &= !(1 << (digit - 1));
+    }
+    total
+}
+
+/**
+ * Options for generating a Killer Sudoku with `Generator::generate_killer`.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KillerOptions {
+    pub(crate) shape: Shape,
+    pub(crate) givens: usize,
+    max_cage: usize,
+}
+
+impl Default for KillerOptions {
+    fn default() -> Self {
+        KillerOptions::new()
+    }
+}
+
+impl KillerOptions {
+    /**
+     * Creates options for a 9x9 puzzle without givens and cages of up to
+     * five cells.
+     */
+    pub fn new() -> Self {
+        KillerOptions {
+            shape: Shape::CLASSIC,
+            givens: 0,
+            max_cage: 5,
+        }
+    }
+
+    /**
+     * Sets the shape of the grid.
+     */
+    pub fn shape(mut self, shape: Shape) -> Self {
+        self.shape = shape;
+        self
+    }
+
+    /**
+     * Sets the number of digits given besides the cages, picked at random.
+     */
+    pub fn givens(mut self, givens: usize) -> Self {
+        self.givens = givens;
+        self
+    }
+
+    /**
+     * Sets the largest number of cells in a cage, between 1 and the grid
+     * size; larger cages make harder puzzles.
+     */
+    pub fn max_cage(mut self, cells: usize) -> Self {
+        self.max_cage = cells;
+        self
+    }
+}
+
+/**
+ * A Killer Sudoku with a unique solution: its cages, its givens, often none,
+ * and that solution.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KillerPuzzle {
+    cages: Cages,
+    givens: Grid,
+    solution: Grid,
+}
+
+impl KillerPuzzle {
+    /**
+     * Builds a puzzle from its cages and givens.
+     * @return The puzzle, or `None` unless there is exactly one solution.
+     */
+    pub fn new(cages: Cages, givens: Grid) -> Option<Self> {
+        let solver = Solver::with_rules(Rules::classic().with(cages.clone()));
+        let mut solutions = solver.solutions(&givens);
+        let solution = solutions.next()?;
+        if solutions.next().is_some() {
+            return None;
+        }
+        Some(KillerPuzzle {
+            cages,
+            givens,
+            solution,
+        })
+    }
+
+    /**
+     * Returns the cages of the puzzle.
+     */
+    pub fn cages(&self) -> &Cages {
+        &self.cages
+    }
+
+    /**
+     * Returns the given digits of the puzzle.
+     */
+    pub fn givens(&self) -> &Grid {
+        &self.givens
+    }
+
+    /**
+     * Returns the unique solution of the puzzle.
+     */
+    pub fn solution(&self) -> &Grid {
+        &self.solution
+    }
+
+    /**
+     * Returns the rules of the puzzle: the classic ones and the cages.
+     */
+    pub fn rules(&self) -> Rules {
+        Rules::classic().with(self.cages.clone())
+    }
+}
+
+/**
+ * Cages a filled grid into a puzzle whose solution is unique.
+ *
+ * Cages are first grown from random cells, one random neighbour at a time,
+ * without repeating a digit. While the puzzle has another solution, the cage
+ * of a cell where the two solutions differ is split in two. Finally,
+ * neighbouring cages are merged, smallest first, wherever the solution stays
+ * unique, so that splitting leaves no more small cages than needed.
+ *
+ * Each check gives up after `CHECK_BUDGET` placements; a split then takes
+ * one of the largest cages, and a merge is skipped.
+ *
+ * @param rng The source of the cage shapes.
+ * @param solution The filled grid.
+ * @param options The givens and cage size.
+ */
+pub(crate) fn cage<R: Rng>(rng: &mut R, solution: Grid, options: &KillerOptions) -> KillerPuzzle {
+    let shape = solution.shape();
+    let size = shape.size();
+    let values = solution.to_values();
+    let max_cage = options.max_cage.clamp(1, size);
+
+    let mut givens = Grid::new(shape);
+    let mut cells: Vec<usize> = (0..shape.cells()).collect();
+    cells.shuffle(rng);
+    for &cell in cells.iter().take(options.givens) {
+        givens
+            .set(cell / size, cell % size, values[cell])
+            .expect("digit in range");
+    }
+
+    let used = |group: &[usize]| group.iter().fold(0, |mask, &cell| mask | bit(values[cell]));
+    let mut groups = grow(rng, &values, size, max_cage);
+    let mut cages = Cages::from_solution(&solution, &groups);
+    loop {
+        let cell = match check(&cages, &givens, &solution) {
+            Check::Unique => break,
+            Check::Other(other) => {
+                let other = other.to_values();
+                let differing: Vec<usize> = (0..values.len())
+                    .filter(|&cell| other[cell] != values[cell])
+                    .collect();
+                *differing.choose(rng).expect("solutions differ")
+            }
+            Check::Unknown => {
+                let largest = groups.iter().map(Vec::len).max().unwrap_or(0);
+                let group = groups
+                    .iter()
+                    .filter(|group| group.len() == largest)
+                    .collect::<Vec<_>>()
+                    .choose(rng)
+                    .copied()
+                    .expect("cages cover the grid");
+                *group.choose(rng).expect("cages are not empty")
+            }
+        };
+        let index = groups
+            .iter()
+            .position(|group| group.contains(&cell))
+            .expect("every cell is caged");
+        let group = groups.swap_remove(index);
+        groups.extend(split(rng, &group, cell, size));
+        cages = Cages::from_solution(&solution, &groups);
+    }
+
+    let mut pairs = Vec::new();
+    for (a, first) in groups.iter().enumerate() {
+        for (b, second) in groups.iter().enumerate().skip(a + 1) {
+            if first
+                .iter()
+                .any(|&cell| neighbours(cell, size).any(|next| second.contains(&next)))
+            {
+                pairs.push((a, b));
+            }
+        }
+    }
+    pairs.shuffle(rng);
+    // Small cages first, so that single cells find a partner while they can.
+    pairs.sort_by_key(|&(a, b)| groups[a].len() + groups[b].len());
+    for (a, b) in pairs {
+        let (first, second) = (&groups[a], &groups[b]);
+        if first.is_empty()
+            || second.is_empty()
+            || first.len() + second.len() > max_cage
+            || used(first) & used(second) != 0
+        {
+            continue;
+        }
+        let mut merged = groups.clone();
+        let moved = std::mem::take(&mut merged[b]);
+        merged[a].extend(moved);
+        let caged: Vec<Vec<usize>> = merged
+            .iter()
+            .filter(|group| !group.is_empty())
+            .cloned()
+            .collect();
+        let candidate = Cages::from_solution(&solution, &caged);
+        if let Check::Unique = check(&candidate, &givens, &solution) {
+            groups = merged;
+            cages = candidate;
+        }
+    }
+
+    KillerPuzzle {
+        cages,
+        givens,
+        solution,
+    }
+}
+
+/**
+ * The placements a uniqueness check may make before giving up; a few
+ * hundredths of a second for a 9x9 grid.
+ */
+const CHECK_BUDGET: usize = 20_000;
+
+/**
+ * The outcome of a uniqueness check of caged givens.
+ */
+enum Check {
+    Unique,
+    /** A solution other than the intended one. */
+    Other(Grid),
+    /** The budget ran out before the search could tell. */
+    Unknown,
+}
+
+/**
+ * Looks for a solution of caged givens other than the intended one, within
+ * `CHECK_BUDGET`.
+ */
+fn check(cages: &Cages, givens: &Grid, solution: &Grid) -> Check {
+    let solver = Solver::with_rules(Rules::classic().with(cages.clone()));
+    let mut solutions = solver.solutions(givens).within(CHECK_BUDGET);
+    match solutions.find(|other| other != solution) {
+        Some(other) => Check::Other(other),
+        None if solutions.ran_out() => Check::Unknown,
+        None => Check::Unique,
+    }
+}
+
+/**
+ * Partitions a filled grid into connected cages of random sizes up to
+ * `max_cage`, none repeating a digit.
+ */
+fn grow<R: Rng>(rng: &mut R, values: &[u8], size: usize, max_cage: usize) -> Vec<Vec<usize>> {
+    let mut owner = vec![None; values.len()];
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.shuffle(rng);
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let used = |group: &[usize]| group.iter().fold(0, |mask, &cell| mask | bit(values[cell]));
+    for start in order {
+        if owner[start].is_some() {
+            continue;
+        }
+        let target = if max_cage > 1 {
+            rng.gen_range(2..=max_cage)
+        } else {
+            1
+        };
+        let mut group = vec![start];
+        owner[start] = Some(groups.len());
+        while group.len() < target {
+            let mut frontier: Vec<usize> = group
+                .iter()
+                .flat_map(|&cell| neighbours(cell, size))
+                .filter(|&next| owner[next].is_none() && used(&group) & bit(values[next]) == 0)
+                .collect();
+            frontier.sort_unstable();
+            frontier.dedup();
+            let Some(&next) = frontier.choose(rng) else {
+                break;
+            };
+            group.push(next);
+            owner[next] = Some(groups.len());
+        }
+        groups.push(group);
+    }
+
+    // A cell left on its own joins a neighbouring cage with room for it.
+    for index in 0..groups.len() {
+        let [cell] = groups[index][..] else {
+            continue;
+        };
+        let mut hosts: Vec<usize> = neighbours(cell, size)
+            .filter_map(|next| owner[next])
+            .filter(|&host| {
+                let group = &groups[host];
+                host != index
+                    && !group.is_empty()
+                    && group.len() < max_cage
+                    && used(group) & bit(values[cell]) == 0
+            })
+            .collect();
+        hosts.sort_unstable();
+        hosts.dedup();
+        if let Some(&host) = hosts.choose(rng) {
+            groups[index].clear();
+            groups[host].push(cell);
+            owner[cell] = Some(host);
+        }
+    }
+    groups.retain(|group| !group.is_empty());
+    groups
+}
+
+/**
+ * Splits a cage in two around one of its cells: a random connected piece
+ * of about half the cage that holds the cell, and the rest, which may fall
+ * apart into several cages.
+ */
+fn split<R: Rng>(rng: &mut R, group: &[usize], cell: usize, size: usize) -> Vec<Vec<usize>> {
+    let mut piece = vec![cell];
+    while piece.len() < group.len() / 2 {
+        let mut frontier: Vec<usize> = piece
+            .iter()
+            .flat_map(|&member| neighbours(member, size))
+            .filter(|next| group.contains(next) && !piece.contains(next))
+            .collect();
+        frontier.sort_unstable();
+        frontier.dedup();
+        let Some(&next) = frontier.choose(rng) else {
+            break;
+        };
+        piece.push(next);
+    }
+    let mut rest: Vec<usize> = group
+        .iter()
+        .copied()
+        .filter(|member| !piece.contains(member))
+        .collect();
+    let mut parts = vec![piece];
+    while let Some(start) = rest.pop() {
+        let mut part = vec![start];
+        let mut index = 0;
+        while index < part.len() {
+            for next in neighbours(part[index], size) {
+                if let Some(position) = rest.iter().position(|&member| member == next) {
+                    part.push(rest.swap_remove(position));
+                }
+            }
+            index += 1;
+        }
+        parts.push(part);
+    }
+    parts
+}
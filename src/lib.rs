@@ -0,0 +1,51 @@
+/*!
+ * Sudoku generation and solving.
+ *
+ * `Grid` holds a board of any supported shape, `Generator` produces filled
+ * grids and `Puzzle`s with a unique solution, and `Solver` solves and counts
+ * solutions. Both follow a set of `Rules`, the classic ones unless a variant
+ * adds its own `Constraint`s.
+ */
+
+mod board;
+mod booklet;
+mod bulk;
+mod constraint;
+mod draw;
+mod format;
+mod generator;
+mod grid;
+mod jigsaw;
+mod killer;
+mod logic;
+mod pdf;
+mod puzzle;
+mod rating;
+mod render;
+mod solver;
+mod svg;
+mod symmetry;
+mod validate;
+
+pub use booklet::{Booklet, BookletOptions, PageSize};
+pub use bulk::{BulkSolver, LineReport, Outcome, Summary};
+pub use constraint::{Boxes, Columns, Constraint, Diagonals, Rows, Rules};
+pub use format::{
+    parse, parse_many, parse_many_shaped, parse_shaped, Format, ParseError, ParseErrorKind,
+};
+pub use generator::{GenerationError, Generator, PuzzleOptions};
+pub use grid::{Grid, GridError, Shape, BOX_SIZE, CELLS, SIZE};
+pub use jigsaw::{RegionError, Regions};
+pub use killer::{Cage, CageError, Cages, KillerOptions, KillerPuzzle};
+pub use logic::{Candidates, LogicalSolver, SolvePath, Step, Technique};
+pub use puzzle::Puzzle;
+pub use rating::{rate, rate_with, weight, Rating, Tier, STUCK_PENALTY};
+pub use render::{Renderer, Style};
+pub use solver::{
+    count_solutions, has_unique_solution, is_minimal, minimize, solutions, Solutions, Solver,
+};
+pub use svg::SvgRenderer;
+pub use symmetry::Symmetry;
+pub use validate::{
+    validate, validate_rows, validate_with, SolutionCount, Unit, Validation, Violation,
+};
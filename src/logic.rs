@@ -0,0 +1,633 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::{bit, digits, layout, Board, Layout, Mask};
+use crate::grid::Grid;
+
+/**
+ * A named deduction technique a human solver would use.
+ *
+ * Variants are declared from easiest to hardest, and `LogicalSolver` tries
+ * them in that order.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Technique {
+    /** A cell with a single candidate. */
+    NakedSingle,
+    /** A digit with a single possible cell in a unit. */
+    HiddenSingle,
+    /** A digit confined to one row or column inside a box. */
+    PointingPair,
+    /** A digit confined to one box inside a row or column. */
+    BoxLineReduction,
+    /** Two cells of a unit sharing the same two candidates. */
+    NakedPair,
+    /** Two digits confined to the same two cells of a unit. */
+    HiddenPair,
+    /** A digit confined to the same two columns in two rows, or vice versa. */
+    XWing,
+    /** Three cells of a unit with three candidates between them. */
+    NakedTriple,
+    /** Three digits confined to the same three cells of a unit. */
+    HiddenTriple,
+    /** A digit confined to the same three columns in three rows, or vice versa. */
+    Swordfish,
+    /** Four cells of a unit with four candidates between them. */
+    NakedQuad,
+    /** Four digits confined to the same four cells of a unit. */
+    HiddenQuad,
+}
+
+impl Technique {
+    /** Every technique, easiest first. */
+    pub const ALL: [Technique; 12] = [
+        Technique::NakedSingle,
+        Technique::HiddenSingle,
+        Technique::PointingPair,
+        Technique::BoxLineReduction,
+        Technique::NakedPair,
+        Technique::HiddenPair,
+        Technique::XWing,
+        Technique::NakedTriple,
+        Technique::HiddenTriple,
+        Technique::Swordfish,
+        Technique::NakedQuad,
+        Technique::HiddenQuad,
+    ];
+
+    /**
+     * Returns the usual human-readable name of the technique.
+     */
+    pub fn name(self) -> &'static str {
+        match self {
+            Technique::NakedSingle => "Naked Single",
+            Technique::HiddenSingle => "Hidden Single",
+            Technique::PointingPair => "Pointing Pair",
+            Technique::BoxLineReduction => "Box/Line Reduction",
+            Technique::NakedPair => "Naked Pair",
+            Technique::HiddenPair => "Hidden Pair",
+            Technique::XWing => "X-Wing",
+            Technique::NakedTriple => "Naked Triple",
+            Technique::HiddenTriple => "Hidden Triple",
+            Technique::Swordfish => "Swordfish",
+            Technique::NakedQuad => "Naked Quad",
+            Technique::HiddenQuad => "Hidden Quad",
+        }
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/**
+ * Reads a technique from its name, ignoring case, spaces and punctuation,
+ * so `X-Wing`, `xwing` and `x_wing` all match.
+ */
+impl FromStr for Technique {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let key = |name: &str| -> String {
+            name.chars()
+                .filter(char::is_ascii_alphanumeric)
+                .map(|c| c.to_ascii_lowercase())
+                .collect()
+        };
+        let wanted = key(s);
+        Technique::ALL
+            .into_iter()
+            .find(|technique| key(technique.name()) == wanted)
+            .ok_or_else(|| format!("unknown technique '{}'", s))
+    }
+}
+
+/**
+ * One deduction: the technique used, the cells that form its pattern, and
+ * either the digit it places or the candidates it eliminates.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub technique: Technique,
+    /** The (row, column) cells the deduction is based on. */
+    pub cells: Vec<(usize, usize)>,
+    /** The (row, column, digit) placed by a single. */
+    pub placement: Option<(usize, usize, u8)>,
+    /** The (row, column, digit) candidates removed. */
+    pub eliminations: Vec<(usize, usize, u8)>,
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.technique)?;
+        if let Some((row, col, digit)) = self.placement {
+            return write!(f, ": r{}c{} = {}", row + 1, col + 1, digit);
+        }
+        f.write_str(" at")?;
+        for &(row, col) in &self.cells {
+            write!(f, " r{}c{}", row + 1, col + 1)?;
+        }
+        f.write_str(": eliminates")?;
+        for (i, &(row, col, digit)) in self.eliminations.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}{} from r{}c{}", separator, digit, row + 1, col + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * A grid with pencil marks: the digit of each filled cell and the remaining
+ * candidates of each blank one.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidates {
+    layout: &'static Layout,
+    values: Vec<u8>,
+    masks: Vec<Mask>,
+}
+
+impl Candidates {
+    /**
+     * Fills in every candidate allowed by the givens of a grid.
+     * @return The candidate grid, or `None` if the givens conflict.
+     */
+    pub fn new(grid: &Grid) -> Option<Self> {
+        let board = Board::new(grid)?;
+        let values = grid.to_values();
+        let masks = values
+            .iter()
+            .enumerate()
+            .map(|(cell, &value)| match value {
+                0 => board.candidates(cell),
+                _ => 0,
+            })
+            .collect();
+        Some(Candidates {
+            layout: layout(grid.shape()),
+            values,
+            masks,
+        })
+    }
+
+    /**
+     * Returns the digit in a cell, or `None` if it is blank.
+     */
+    pub fn get(&self, row: usize, col: usize) -> Option<u8> {
+        match self.values[row * self.layout.size + col] {
+            0 => None,
+            value => Some(value),
+        }
+    }
+
+    /**
+     * Returns the remaining candidates of a cell, empty if it is filled.
+     */
+    pub fn candidates(&self, row: usize, col: usize) -> Vec<u8> {
+        digits(self.masks[row * self.layout.size + col]).collect()
+    }
+
+    /**
+     * Returns true if every cell is filled.
+     */
+    pub fn is_solved(&self) -> bool {
+        self.values.iter().all(|&value| value != 0)
+    }
+
+    /**
+     * Returns the filled cells as a grid.
+     */
+    pub fn to_grid(&self) -> Grid {
+        Grid::with_values(self.layout.shape, &self.values).expect("digits are in range")
+    }
+
+    /**
+     * Applies a deduction: places its digit, removing it from the candidates
+     * of every peer, and removes its eliminated candidates.
+     */
+    pub fn apply(&mut self, step: &Step) {
+        let size = self.layout.size;
+        if let Some((row, col, digit)) = step.placement {
+            self.place(row * size + col, digit);
+        }
+        for &(row, col, digit) in &step.eliminations {
+            self.masks[row * size + col] &= !bit(digit);
+        }
+    }
+
+    fn place(&mut self, cell: usize, digit: u8) {
+        let layout = self.layout;
+        self.values[cell] = digit;
+        self.masks[cell] = 0;
+        let units = [
+            layout.row(cell),
+            layout.size + layout.col(cell),
+            2 * layout.size + layout.box_index(cell),
+        ];
+        for unit in units {
+            for &peer in &layout.units[unit] {
+                self.masks[peer] &= !bit(digit);
+            }
+        }
+    }
+}
+
+/**
+ * The deductions a logical solve made, in order, and the grid it reached.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolvePath {
+    pub steps: Vec<Step>,
+    pub grid: Grid,
+}
+
+impl SolvePath {
+    /**
+     * Returns true if the techniques were enough to fill the grid.
+     */
+    pub fn is_solved(&self) -> bool {
+        self.grid.is_complete()
+    }
+
+    /**
+     * Returns the hardest technique the path used.
+     */
+    pub fn hardest(&self) -> Option<Technique> {
+        self.steps.iter().map(|step| step.technique).max()
+    }
+}
+
+/**
+ * Step-by-step solver that only makes deductions a human could, trying the
+ * easiest applicable technique at each step.
+ */
+#[derive(Debug, Clone)]
+pub struct LogicalSolver {
+    techniques: Vec<Technique>,
+}
+
+impl LogicalSolver {
+    /**
+     * Creates a solver that knows every technique.
+     */
+    pub fn new() -> Self {
+        LogicalSolver::with_techniques(&Technique::ALL)
+    }
+
+    /**
+     * Creates a solver restricted to some techniques, tried easiest first.
+     */
+    pub fn with_techniques(techniques: &[Technique]) -> Self {
+        let mut techniques = techniques.to_vec();
+        techniques.sort();
+        techniques.dedup();
+        LogicalSolver { techniques }
+    }
+
+    /**
+     * Finds the next deduction, using the easiest technique that applies.
+     * @return The step, or `None` if the solver is stuck or the grid is full.
+     */
+    pub fn next_step(&self, candidates: &Candidates) -> Option<Step> {
+        self.techniques
+            .iter()
+            .find_map(|&technique| find(technique, candidates))
+    }
+
+    /**
+     * Solves a grid as far as the techniques allow.
+     * @param grid The puzzle to solve.
+     * @return Every step taken and the grid reached, or `None` if the givens
+     * conflict.
+     */
+    pub fn solve(&self, grid: &Grid) -> Option<SolvePath> {
+        let mut candidates = Candidates::new(grid)?;
+        let mut steps = Vec::new();
+        while let Some(step) = self.next_step(&candidates) {
+            candidates.apply(&step);
+            steps.push(step);
+        }
+        Some(SolvePath {
+            steps,
+            grid: candidates.to_grid(),
+        })
+    }
+}
+
+impl Default for LogicalSolver {
+    fn default() -> Self {
+        LogicalSolver::new()
+    }
+}
+
+fn find(technique: Technique, candidates: &Candidates) -> Option<Step> {
+    match technique {
+        Technique::NakedSingle => naked_single(candidates),
+        Technique::HiddenSingle => hidden_single(candidates),
+        Technique::PointingPair => pointing(candidates),
+        Technique::BoxLineReduction => box_line_reduction(candidates),
+        Technique::NakedPair => naked_subset(candidates, 2, technique),
+        Technique::NakedTriple => naked_subset(candidates, 3, technique),
+        Technique::NakedQuad => naked_subset(candidates, 4, technique),
+        Technique::HiddenPair => hidden_subset(candidates, 2, technique),
+        Technique::HiddenTriple => hidden_subset(candidates, 3, technique),
+        Technique::HiddenQuad => hidden_subset(candidates, 4, technique),
+        Technique::XWing => fish(candidates, 2, technique),
+        Technique::Swordfish => fish(candidates, 3, technique),
+    }
+}
+
+fn placement(technique: Technique, candidates: &Candidates, cell: usize, digit: u8) -> Step {
+    let (row, col) = candidates.layout.coordinates(cell);
+    Step {
+        technique,
+        cells: vec![(row, col)],
+        placement: Some((row, col, digit)),
+        eliminations: Vec::new(),
+    }
+}
+
+/**
+ * Builds an elimination step, or `None` if it would not remove anything.
+ * @param pattern The cells the deduction is based on.
+ * @param targets The cells to remove candidates from, with the digits to remove.
+ */
+fn elimination(
+    technique: Technique,
+    candidates: &Candidates,
+    pattern: impl Iterator<Item = usize>,
+    targets: impl Iterator<Item = (usize, Mask)>,
+) -> Option<Step> {
+    let layout = candidates.layout;
+    let mut eliminations = Vec::new();
+    for (cell, mask) in targets {
+        let (row, col) = layout.coordinates(cell);
+        for digit in digits(candidates.masks[cell] & mask) {
+            eliminations.push((row, col, digit));
+        }
+    }
+    if eliminations.is_empty() {
+        return None;
+    }
+    Some(Step {
+        technique,
+        cells: pattern.map(|cell| layout.coordinates(cell)).collect(),
+        placement: None,
+        eliminations,
+    })
+}
+
+/**
+ * Returns the positions, as a bitmask over the unit's cells, at which a
+ * digit is still a candidate in a unit.
+ */
+fn positions(candidates: &Candidates, unit: usize, digit: u8) -> Mask {
+    positions_where(candidates, unit, |cell| {
+        candidates.masks[cell] & bit(digit) != 0
+    })
+}
+
+/**
+ * Returns the positions of the cells of a unit that satisfy a predicate.
+ */
+fn positions_where(
+    candidates: &Candidates,
+    unit: usize,
+    predicate: impl Fn(usize) -> bool,
+) -> Mask {
+    let mut positions = 0;
+    for (i, &cell) in candidates.layout.units[unit].iter().enumerate() {
+        if predicate(cell) {
+            positions |= 1 << i;
+        }
+    }
+    positions
+}
+
+/**
+ * Returns the digits not yet placed in a unit.
+ */
+fn unplaced(candidates: &Candidates, unit: usize) -> Mask {
+    candidates.layout.units[unit]
+        .iter()
+        .fold(candidates.layout.all, |mask, &cell| {
+            match candidates.values[cell] {
+                0 => mask,
+                value => mask & !bit(value),
+            }
+        })
+}
+
+fn naked_single(candidates: &Candidates) -> Option<Step> {
+    (0..candidates.layout.cells).find_map(|cell| {
+        let mask = candidates.masks[cell];
+        if candidates.values[cell] == 0 && mask.count_ones() == 1 {
+            let digit = digits(mask).next()?;
+            Some(placement(Technique::NakedSingle, candidates, cell, digit))
+        } else {
+            None
+        }
+    })
+}
+
+fn hidden_single(candidates: &Candidates) -> Option<Step> {
+    for unit in 0..candidates.layout.unit_count() {
+        for digit in digits(unplaced(candidates, unit)) {
+            let positions = positions(candidates, unit, digit);
+            if positions.count_ones() == 1 {
+                let cell = cells_at(candidates, unit, positions).next()?;
+                return Some(placement(Technique::HiddenSingle, candidates, cell, digit));
+            }
+        }
+    }
+    None
+}
+
+/**
+ * Returns the cells of a unit selected by a position bitmask.
+ */
+fn cells_at(candidates: &Candidates, unit: usize, positions: Mask) -> impl Iterator<Item = usize> {
+    let cells = &candidates.layout.units[unit];
+    (0..cells.len())
+        .filter(move |i| positions & (1 << i) != 0)
+        .map(move |i| cells[i])
+}
+
+/**
+ * Finds a digit whose candidates in one of the units `from` all lie in the
+ * same unit `to(cell)`, and eliminates it from the rest of that unit.
+ */
+fn confined(
+    candidates: &Candidates,
+    technique: Technique,
+    from: std::ops::Range<usize>,
+    to: impl Fn(usize) -> usize,
+) -> Option<Step> {
+    let units = &candidates.layout.units;
+    for unit in from {
+        for digit in digits(unplaced(candidates, unit)) {
+            let positions = positions(candidates, unit, digit);
+            if positions.count_ones() < 2 {
+                continue;
+            }
+            let mut cells = cells_at(candidates, unit, positions);
+            let other = to(cells.next()?);
+            if !cells.all(|cell| to(cell) == other) {
+                continue;
+            }
+            let targets = units[other]
+                .iter()
+                .filter(|cell| !units[unit].contains(cell))
+                .map(|&cell| (cell, bit(digit)));
+            let pattern = cells_at(candidates, unit, positions);
+            if let Some(step) = elimination(technique, candidates, pattern, targets) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+fn pointing(candidates: &Candidates) -> Option<Step> {
+    let layout = candidates.layout;
+    let size = layout.size;
+    let boxes = 2 * size..3 * size;
+    confined(candidates, Technique::PointingPair, boxes.clone(), |cell| {
+        layout.row(cell)
+    })
+    .or_else(|| {
+        confined(candidates, Technique::PointingPair, boxes, |cell| {
+            size + layout.col(cell)
+        })
+    })
+}
+
+fn box_line_reduction(candidates: &Candidates) -> Option<Step> {
+    let layout = candidates.layout;
+    confined(
+        candidates,
+        Technique::BoxLineReduction,
+        0..2 * layout.size,
+        |cell| 2 * layout.size + layout.box_index(cell),
+    )
+}
+
+/**
+ * Iterates over the subsets of `set` with exactly `size` members, in
+ * increasing order.
+ *
+ * Combinations of the members' indices are stepped with Gosper's hack, so
+ * only the subsets of the right size are visited.
+ */
+fn subsets(set: Mask, size: u32) -> impl Iterator<Item = Mask> {
+    let members: Vec<Mask> = digits(set).map(bit).collect();
+    let end = 1u64 << members.len();
+    let mut combination: u64 = if size == 0 || size as usize > members.len() {
+        end
+    } else {
+        (1 << size) - 1
+    };
+    std::iter::from_fn(move || {
+        if combination >= end {
+            return None;
+        }
+        let subset = (0..members.len())
+            .filter(|i| combination & (1 << i) != 0)
+            .fold(0, |subset, i| subset | members[i]);
+        let lowest = combination & combination.wrapping_neg();
+        let ripple = combination + lowest;
+        combination = (((ripple ^ combination) >> 2) / lowest) | ripple;
+        Some(subset)
+    })
+}
+
+fn naked_subset(candidates: &Candidates, size: u32, technique: Technique) -> Option<Step> {
+    for unit in 0..candidates.layout.unit_count() {
+        let blank = positions_where(candidates, unit, |cell| candidates.values[cell] == 0);
+        if blank.count_ones() <= size {
+            continue;
+        }
+        for subset in subsets(blank, size) {
+            let digits = cells_at(candidates, unit, subset)
+                .fold(0, |mask, cell| mask | candidates.masks[cell]);
+            if digits.count_ones() != size {
+                continue;
+            }
+            let targets = cells_at(candidates, unit, blank & !subset).map(|cell| (cell, digits));
+            let pattern = cells_at(candidates, unit, subset);
+            if let Some(step) = elimination(technique, candidates, pattern, targets) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+fn hidden_subset(candidates: &Candidates, size: u32, technique: Technique) -> Option<Step> {
+    let layout = candidates.layout;
+    for unit in 0..layout.unit_count() {
+        let unplaced = unplaced(candidates, unit);
+        if unplaced.count_ones() <= size {
+            continue;
+        }
+        let positions: Vec<Mask> = (1..=layout.size as u8)
+            .map(|digit| positions(candidates, unit, digit))
+            .collect();
+        for subset in subsets(unplaced, size) {
+            if digits(subset).any(|digit| positions[digit as usize - 1] == 0) {
+                continue;
+            }
+            let cells = digits(subset).fold(0, |mask, digit| mask | positions[digit as usize - 1]);
+            if cells.count_ones() != size {
+                continue;
+            }
+            let targets = cells_at(candidates, unit, cells).map(|cell| (cell, !subset));
+            let pattern = cells_at(candidates, unit, cells);
+            if let Some(step) = elimination(technique, candidates, pattern, targets) {
+                return Some(step);
+            }
+        }
+    }
+    None
+}
+
+/**
+ * Finds `size` rows (or columns) in which a digit's candidates all lie in
+ * the same `size` columns (or rows), and eliminates the digit from the rest
+ * of those columns (or rows).
+ */
+fn fish(candidates: &Candidates, size: u32, technique: Technique) -> Option<Step> {
+    let layout = candidates.layout;
+    let lines = layout.size;
+    for (base, cover) in [(0, lines), (lines, 0)] {
+        for digit in 1..=lines as u8 {
+            let positions: Vec<Mask> = (0..lines)
+                .map(|line| positions(candidates, base + line, digit))
+                .collect();
+            let eligible = (0..lines)
+                .filter(|&line| (2..=size).contains(&positions[line].count_ones()))
+                .fold(0, |set: Mask, line| set | 1 << line);
+            for subset in subsets(eligible, size) {
+                let covered = (0..lines)
+                    .filter(|line| subset & (1 << line) != 0)
+                    .fold(0, |set, line| set | positions[line]);
+                if covered.count_ones() != size {
+                    continue;
+                }
+                let pattern = (0..lines)
+                    .filter(|line| subset & (1 << line) != 0)
+                    .flat_map(|line| cells_at(candidates, base + line, positions[line]));
+                let targets = (0..lines)
+                    .filter(|line| covered & (1 << line) != 0)
+                    .flat_map(|line| cells_at(candidates, cover + line, !subset & layout.all))
+                    .map(|cell| (cell, bit(digit)));
+                if let Some(step) = elimination(technique, candidates, pattern, targets) {
+                    return Some(step);
+                }
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,274 @@
+/*!
+ * A minimal PDF writer: pages of lines and text in the standard Helvetica
+ * fonts, which every reader has, so nothing needs embedding.
+ */
+
+use std::fmt::Write;
+
+use crate::draw::{number, Canvas, Ink};
+
+/**
+ * Widths of the printable ASCII glyphs of Helvetica, in thousandths of the
+ * font size, from its font metrics.
+ */
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/**
+ * Returns the width of text set in Helvetica. Bold is slightly wider; the
+ * regular metrics are close enough for centring short labels.
+ */
+pub(crate) fn text_width(text: &str, size: f64) -> f64 {
+    let thousandths: u32 = text
+        .chars()
+        .map(|c| match c {
+            ' '..='~' => u32::from(HELVETICA_WIDTHS[c as usize - 32]),
+            _ => 556,
+        })
+        .sum();
+    f64::from(thousandths) * size / 1000.0
+}
+
+/**
+ * One page being drawn. Coordinates are in points from the top-left corner,
+ * like the other canvases; they are flipped into PDF space when written.
+ */
+pub(crate) struct Page {
+    height: f64,
+    content: String,
+}
+
+impl Page {
+    /**
+     * Writes text with its baseline starting at `(x, y)`.
+     */
+    pub(crate) fn label(&mut self, x: f64, y: f64, size: f64, bold: bool, text: &str) {
+        let font = if bold { "F2" } else { "F1" };
+        writeln!(
+            self.content,
+            "BT /{} {} Tf 0 g {} {} Td ({}) Tj ET",
+            font,
+            number(size),
+            number(x),
+            number(self.height - y),
+            escape(text)
+        )
+        .unwrap();
+    }
+
+    /**
+     * Strokes straight segments with square caps.
+     * @param style Extra graphics state operators, each with a leading space.
+     */
+    fn stroke(&mut self, segments: &[(f64, f64, f64, f64)], width: f64, style: &str, ink: Ink) {
+        write!(
+            self.content,
+            "q {} G {} w 2 J{}",
+            gray(ink),
+            number(width),
+            style
+        )
+        .unwrap();
+        for &(x1, y1, x2, y2) in segments {
+            write!(
+                self.content,
+                " {} {} m {} {} l",
+                number(x1),
+                number(self.height - y1),
+                number(x2),
+                number(self.height - y2)
+            )
+            .unwrap();
+        }
+        self.content.push_str(" S Q\n");
+    }
+
+    /**
+     * Writes text with its baseline centred on `(x, y)`.
+     */
+    pub(crate) fn centered_label(&mut self, x: f64, y: f64, size: f64, bold: bool, text: &str) {
+        self.label(x - text_width(text, size) / 2.0, y, size, bold, text);
+    }
+}
+
+/**
+ * Returns the gray level of an ink: print output stays black and white.
+ */
+fn gray(ink: Ink) -> &'static str {
+    match ink {
+        Ink::Line | Ink::Given => "0",
+        Ink::Solved => "0.2",
+        Ink::Mark => "0.45",
+        Ink::Shade => "0.88",
+        Ink::Cage => "0.25",
+        Ink::Paper => "1",
+    }
+}
+
+impl Canvas for Page {
+    fn lines(&mut self, segments: &[(f64, f64, f64, f64)], width: f64, ink: Ink) {
+        self.stroke(segments, width, "", ink);
+    }
+
+    fn dashed_lines(&mut self, segments: &[(f64, f64, f64, f64)], width: f64, dash: f64, ink: Ink) {
+        self.stroke(segments, width, &format!(" [{}] 0 d", number(dash)), ink);
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, ink: Ink) {
+        writeln!(
+            self.content,
+            "q {} g {} {} {} {} re f Q",
+            gray(ink),
+            number(x),
+            number(self.height - y - height),
+            number(width),
+            number(height)
+        )
+        .unwrap();
+    }
+
+    fn text(&mut self, x: f64, y: f64, size: f64, bold: bool, ink: Ink, text: &str) {
+        // Digits are about 0.7 em tall, so the baseline sits 0.35 em below
+        // the centre.
+        let x = x - text_width(text, size) / 2.0;
+        let font = if bold { "F2" } else { "F1" };
+        writeln!(
+            self.content,
+            "BT /{} {} Tf {} g {} {} Td ({}) Tj ET",
+            font,
+            number(size),
+            gray(ink),
+            number(x),
+            number(self.height - y - 0.35 * size),
+            escape(text)
+        )
+        .unwrap();
+    }
+}
+
+/**
+ * A document of equally sized pages.
+ */
+pub(crate) struct Document {
+    width: f64,
+    height: f64,
+    title: String,
+    pages: Vec<Page>,
+}
+
+impl Document {
+    /**
+     * Creates an empty document.
+     * @param width The page width in points.
+     * @param height The page height in points.
+     * @param title The title stored in the document information.
+     */
+    pub(crate) fn new(width: f64, height: f64, title: &str) -> Self {
+        Document {
+            width,
+            height,
+            title: title.to_string(),
+            pages: Vec::new(),
+        }
+    }
+
+    /**
+     * Starts a new page and returns it for drawing.
+     */
+    pub(crate) fn add_page(&mut self) -> &mut Page {
+        self.pages.push(Page {
+            height: self.height,
+            content: String::new(),
+        });
+        self.pages.last_mut().expect("page was just added")
+    }
+
+    /**
+     * Serializes the document.
+     *
+     * Objects are the catalog, the page tree, the two fonts and the
+     * document information, followed by a page and a content stream for
+     * every page.
+     */
+    pub(crate) fn finish(self) -> Vec<u8> {
+        const FIXED: usize = 5;
+        let page_id = |index: usize| FIXED + 1 + 2 * index;
+        let kids: Vec<String> = (0..self.pages.len())
+            .map(|index| format!("{} 0 R", page_id(index)))
+            .collect();
+
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                self.pages.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+             /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica-Bold \
+             /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            format!("<< /Title ({}) /Producer (sudoku) >>", escape(&self.title)),
+        ];
+        for (index, page) in self.pages.iter().enumerate() {
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                number(self.width),
+                number(self.height),
+                page_id(index) + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}endstream",
+                page.content.len(),
+                page.content
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            writeln!(pdf, "{} 0 obj\n{}\nendobj", index + 1, object).unwrap();
+        }
+        let xref = pdf.len();
+        writeln!(pdf, "xref\n0 {}\n0000000000 65535 f ", objects.len() + 1).unwrap();
+        for offset in offsets {
+            writeln!(pdf, "{:010} 00000 n ", offset).unwrap();
+        }
+        write!(
+            pdf,
+            "trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        )
+        .unwrap();
+        pdf.into_bytes()
+    }
+}
+
+/**
+ * Escapes a PDF string literal. Only ASCII is written; anything else
+ * becomes `?`.
+ */
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            ' '..='~' => escaped.push(c),
+            _ => escaped.push('?'),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,56 @@
+use crate::grid::Grid;
+use crate::solver::Solver;
+
+/**
+ * A puzzle with a unique solution: its givens together with that solution.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Puzzle {
+    givens: Grid,
+    solution: Grid,
+}
+
+impl Puzzle {
+    /**
+     * Builds a puzzle from its givens.
+     * @param givens The clues of the puzzle.
+     * @return The puzzle, or `None` unless the givens have exactly one solution.
+     */
+    pub fn new(givens: Grid) -> Option<Self> {
+        let solver = Solver::new();
+        if !solver.has_unique_solution(&givens) {
+            return None;
+        }
+        let solution = solver.solve(&givens)?;
+        Some(Puzzle { givens, solution })
+    }
+
+    /**
+     * Pairs givens with a solution the caller has already checked to be
+     * their unique solution.
+     */
+    pub(crate) fn from_parts(givens: Grid, solution: Grid) -> Self {
+        Puzzle { givens, solution }
+    }
+
+    /**
+     * Returns the clues of the puzzle.
+     */
+    pub fn givens(&self) -> &Grid {
+        &self.givens
+    }
+
+    /**
+     * Returns the unique solution of the puzzle.
+     */
+    pub fn solution(&self) -> &Grid {
+        &self.solution
+    }
+
+    /**
+     * Returns the number of clues.
+     */
+    pub fn clues(&self) -> usize {
+        self.givens.filled_count()
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::grid::Grid;
+use crate::logic::{LogicalSolver, Technique};
+
+/**
+ * Difficulty tier of a puzzle, decided by the hardest technique it needs.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tier {
+    /** Singles only. */
+    Easy,
+    /** Needs pointing pairs or box/line reduction. */
+    Medium,
+    /** Needs naked or hidden pairs. */
+    Hard,
+    /** Needs X-Wings, Swordfish, triples or quads. */
+    Expert,
+    /** Cannot be finished with the known techniques. */
+    Diabolical,
+}
+
+impl Tier {
+    /** Every tier, easiest first. */
+    pub const ALL: [Tier; 5] = [
+        Tier::Easy,
+        Tier::Medium,
+        Tier::Hard,
+        Tier::Expert,
+        Tier::Diabolical,
+    ];
+
+    /**
+     * Returns the lowercase name of the tier.
+     */
+    pub fn name(self) -> &'static str {
+        match self {
+            Tier::Easy => "easy",
+            Tier::Medium => "medium",
+            Tier::Hard => "hard",
+            Tier::Expert => "expert",
+            Tier::Diabolical => "diabolical",
+        }
+    }
+
+    /**
+     * Returns the tier of puzzles whose hardest step uses a technique.
+     */
+    pub fn of(technique: Technique) -> Tier {
+        match technique {
+            Technique::NakedSingle | Technique::HiddenSingle => Tier::Easy,
+            Technique::PointingPair | Technique::BoxLineReduction => Tier::Medium,
+            Technique::NakedPair | Technique::HiddenPair => Tier::Hard,
+            Technique::XWing
+            | Technique::NakedTriple
+            | Technique::HiddenTriple
+            | Technique::Swordfish
+            | Technique::NakedQuad
+            | Technique::HiddenQuad => Tier::Expert,
+        }
+    }
+}
+
+impl fmt::Display for Tier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Tier {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Tier::ALL
+            .into_iter()
+            .find(|tier| tier.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown difficulty '{}'", s))
+    }
+}
+
+/**
+ * How hard a puzzle is for a human solver.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rating {
+    /**
+     * Grows with the hardest technique needed and with the number of times
+     * each technique is applied; comparable across tiers.
+     */
+    pub score: u32,
+    pub tier: Tier,
+    /** The hardest technique on the solve path. */
+    pub hardest: Option<Technique>,
+    /** How many steps used each technique. */
+    pub counts: BTreeMap<Technique, usize>,
+    /** False if the techniques got stuck before the grid was full. */
+    pub solved: bool,
+}
+
+impl fmt::Display for Rating {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (score {})", self.tier, self.score)
+    }
+}
+
+/** Score added when the logical solver cannot finish the puzzle. */
+pub const STUCK_PENALTY: u32 = 1000;
+
+/**
+ * Returns the score weight of a single application of a technique.
+ */
+pub fn weight(technique: Technique) -> u32 {
+    match technique {
+        Technique::NakedSingle => 1,
+        Technique::HiddenSingle => 2,
+        Technique::PointingPair => 5,
+        Technique::BoxLineReduction => 6,
+        Technique::NakedPair => 8,
+        Technique::HiddenPair => 10,
+        Technique::XWing => 12,
+        Technique::NakedTriple => 14,
+        Technique::HiddenTriple => 16,
+        Technique::Swordfish => 18,
+        Technique::NakedQuad => 20,
+        Technique::HiddenQuad => 24,
+    }
+}
+
+/**
+ * Rates a puzzle by solving it with the logical solver.
+ *
+ * The score is twenty times the weight of the hardest technique plus the
+ * weight of every step taken, and a fixed penalty if the solver gets stuck.
+ *
+ * @param grid The puzzle to rate.
+ * @return The rating, or `None` if the givens conflict.
+ */
+pub fn rate(grid: &Grid) -> Option<Rating> {
+    rate_with(&LogicalSolver::new(), grid)
+}
+
+/**
+ * Rates a puzzle using the techniques a given solver knows.
+ */
+pub fn rate_with(solver: &LogicalSolver, grid: &Grid) -> Option<Rating> {
+    let path = solver.solve(grid)?;
+    let mut counts = BTreeMap::new();
+    for step in &path.steps {
+        *counts.entry(step.technique).or_insert(0) += 1;
+    }
+    let hardest = path.hardest();
+    let solved = path.is_solved();
+    let mut score = 20 * hardest.map_or(0, weight);
+    score += path
+        .steps
+        .iter()
+        .map(|step| weight(step.technique))
+        .sum::<u32>();
+    let tier = if solved {
+        hardest.map_or(Tier::Easy, Tier::of)
+    } else {
+        score += STUCK_PENALTY;
+        Tier::Diabolical
+    };
+    Some(Rating {
+        score,
+        tier,
+        hardest,
+        counts,
+        solved,
+    })
+}
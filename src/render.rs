@@ -0,0 +1,380 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::conflicts;
+use crate::constraint::Rules;
+use crate::grid::{symbol, Grid, Shape};
+use crate::jigsaw::Regions;
+
+/**
+ * The characters a grid is drawn with.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Style {
+    /** Unicode box-drawing lines. */
+    #[default]
+    Unicode,
+    /** `+`, `-` and `|` only, for plain-text documents. */
+    Ascii,
+}
+
+/**
+ * The pieces of a frame: one string for each position a line can take.
+ */
+struct Frame {
+    horizontal: &'static str,
+    vertical: &'static str,
+    /** Left, middle and right joints of the top, inner and bottom lines. */
+    joints: [[&'static str; 3]; 3],
+}
+
+const UNICODE: Frame = Frame {
+    horizontal: "─",
+    vertical: "│",
+    joints: [["┌", "┬", "┐"], ["├", "┼", "┤"], ["└", "┴", "┘"]],
+};
+
+const ASCII: Frame = Frame {
+    horizontal: "-",
+    vertical: "|",
+    joints: [["+", "+", "+"], ["+", "+", "+"], ["+", "+", "+"]],
+};
+
+impl Style {
+    /** Every style. */
+    pub const ALL: [Style; 2] = [Style::Unicode, Style::Ascii];
+
+    /**
+     * Returns the short name used on the command line.
+     */
+    pub fn name(self) -> &'static str {
+        match self {
+            Style::Unicode => "unicode",
+            Style::Ascii => "ascii",
+        }
+    }
+
+    fn frame(self) -> &'static Frame {
+        match self {
+            Style::Unicode => &UNICODE,
+            Style::Ascii => &ASCII,
+        }
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Style::ALL
+            .into_iter()
+            .find(|style| style.name().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("unknown style '{}'", s))
+    }
+}
+
+/** ANSI escape for givens: bold. */
+const GIVEN: &str = "\x1b[1m";
+/** ANSI escape for digits filled in by solving: cyan. */
+const SOLVED: &str = "\x1b[36m";
+/** ANSI escape for digits that repeat in a unit: bold red. */
+const CONFLICT: &str = "\x1b[1;31m";
+/** ANSI escape for the background of diagonal cells: gray. */
+const SHADE: &str = "\x1b[100m";
+/** Blank diagonal cells without colors, which cannot shade them. */
+const DIAGONAL_BLANK: char = '*';
+/** ANSI escape for the frame: dim. */
+const LINES: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+/**
+ * Draws grids for the terminal, with box separators and optional colors.
+ *
+ * Colors are off unless requested; callers writing to a terminal decide with
+ * `std::io::IsTerminal`. Killer cages are not drawn: only `SvgRenderer`
+ * outlines them.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Renderer {
+    style: Style,
+    blank: char,
+    color: bool,
+    diagonals: bool,
+    regions: Option<Regions>,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Renderer::new()
+    }
+}
+
+impl Renderer {
+    /**
+     * Creates a renderer drawing Unicode lines, `.` for blanks and no color.
+     */
+    pub fn new() -> Self {
+        Renderer {
+            style: Style::Unicode,
+            blank: '.',
+            color: false,
+            diagonals: false,
+            regions: None,
+        }
+    }
+
+    /**
+     * Sets the characters the frame is drawn with.
+     */
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /**
+     * Sets the character shown in blank cells, usually `.` or a space.
+     */
+    pub fn blank(mut self, blank: char) -> Self {
+        self.blank = blank;
+        self
+    }
+
+    /**
+     * Turns ANSI colors on or off.
+     */
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /**
+     * Marks the cells of both main diagonals, for Sudoku-X puzzles, and
+     * highlights digits repeated along them. With colors on the cells are
+     * shaded; without, blank diagonal cells are drawn as `*`.
+     */
+    pub fn diagonals(mut self, diagonals: bool) -> Self {
+        self.diagonals = diagonals;
+        self
+    }
+
+    /**
+     * Draws walls along the regions of a Jigsaw Sudoku instead of the box
+     * separators, widening the cells to make room for them. Drawing a grid
+     * of another size then panics.
+     */
+    pub fn regions(mut self, regions: &Regions) -> Self {
+        self.regions = Some(regions.clone());
+        self
+    }
+
+    /**
+     * Draws a grid, treating every digit as a given.
+     * @return The drawing, ending with a newline.
+     */
+    pub fn render(&self, grid: &Grid) -> String {
+        self.render_solved(grid, grid)
+    }
+
+    /**
+     * Draws a grid filled in from a puzzle: digits that are not among the
+     * givens are shown as solved, and digits repeated in a row, column, box
+     * or marked diagonal as conflicting.
+     * @param givens The clues of the puzzle.
+     * @param grid The grid to draw, normally the givens plus placed digits.
+     * @return The drawing, ending with a newline.
+     */
+    pub fn render_solved(&self, givens: &Grid, grid: &Grid) -> String {
+        if let Some(regions) = &self.regions {
+            assert_eq!(
+                regions.shape().size(),
+                grid.size(),
+                "the regions are for another grid size"
+            );
+            return self.render_regions(givens, grid, regions);
+        }
+        let rules = if self.diagonals {
+            Rules::diagonal()
+        } else {
+            Rules::classic()
+        };
+        let conflicting = conflicts(grid, &rules.layout(grid.shape()));
+        let frame = self.style.frame();
+        let shape = grid.shape();
+        let size = shape.size();
+        let mut text = String::with_capacity(16 * shape.cells());
+        for row in 0..size {
+            if row % shape.box_rows() == 0 {
+                self.push_line(&mut text, frame, shape, if row == 0 { 0 } else { 1 });
+            }
+            for col in 0..size {
+                if col % shape.box_cols() == 0 {
+                    if col > 0 {
+                        text.push(' ');
+                    }
+                    self.push_paint(&mut text, LINES, frame.vertical);
+                }
+                text.push(' ');
+                self.push_cell(&mut text, givens, grid, &conflicting, row, col);
+            }
+            text.push(' ');
+            self.push_paint(&mut text, LINES, frame.vertical);
+            text.push('\n');
+        }
+        self.push_line(&mut text, frame, shape, 2);
+        text
+    }
+
+    /**
+     * Draws a grid with walls along the boundaries of jigsaw regions, each
+     * cell padded by a space on both sides to leave room for them.
+     */
+    fn render_regions(&self, givens: &Grid, grid: &Grid, regions: &Regions) -> String {
+        let rules = Rules::jigsaw(regions.clone());
+        let conflicting = conflicts(grid, &rules.layout(grid.shape()));
+        let frame = self.style.frame();
+        let size = grid.size();
+        // Whether the edge left of a cell, or above it when `across` is
+        // false, separates two regions or is part of the frame.
+        let wall = |across: bool, row: usize, col: usize| {
+            if across {
+                col == 0
+                    || col == size
+                    || regions.region_of(row, col - 1) != regions.region_of(row, col)
+            } else {
+                row == 0
+                    || row == size
+                    || regions.region_of(row - 1, col) != regions.region_of(row, col)
+            }
+        };
+        let mut text = String::with_capacity(32 * grid.shape().cells());
+        for row in 0..=size {
+            let mut line = String::new();
+            for col in 0..=size {
+                let up = row > 0 && wall(true, row - 1, col);
+                let down = row < size && wall(true, row, col);
+                let left = col > 0 && wall(false, row, col - 1);
+                let right = col < size && wall(false, row, col);
+                let vertical = match (up, down) {
+                    (false, true) => Some(0),
+                    (true, true) => Some(1),
+                    (true, false) => Some(2),
+                    (false, false) => None,
+                };
+                let horizontal = match (left, right) {
+                    (false, true) => Some(0),
+                    (true, true) => Some(1),
+                    (true, false) => Some(2),
+                    (false, false) => None,
+                };
+                line.push_str(match (vertical, horizontal) {
+                    (Some(v), Some(h)) => frame.joints[v][h],
+                    (Some(_), None) => frame.vertical,
+                    (None, Some(_)) => frame.horizontal,
+                    (None, None) => " ",
+                });
+                if col < size {
+                    line.push_str(&if right {
+                        frame.horizontal.repeat(3)
+                    } else {
+                        "   ".to_string()
+                    });
+                }
+            }
+            self.push_paint(&mut text, LINES, &line);
+            text.push('\n');
+            if row == size {
+                break;
+            }
+            for col in 0..=size {
+                if wall(true, row, col) {
+                    self.push_paint(&mut text, LINES, frame.vertical);
+                } else {
+                    text.push(' ');
+                }
+                if col < size {
+                    text.push(' ');
+                    self.push_cell(&mut text, givens, grid, &conflicting, row, col);
+                    text.push(' ');
+                }
+            }
+            text.push('\n');
+        }
+        text
+    }
+
+    /**
+     * Appends the digit or blank of a cell, painted as a given, solved or
+     * conflicting digit and shaded on a diagonal, or marked there when
+     * colors are off.
+     */
+    fn push_cell(
+        &self,
+        text: &mut String,
+        givens: &Grid,
+        grid: &Grid,
+        conflicting: &[bool],
+        row: usize,
+        col: usize,
+    ) {
+        let size = grid.size();
+        let shaded = self.diagonals && (row == col || row + col + 1 == size);
+        let (paint, content) = match grid.get(row, col) {
+            None if shaded && !self.color => ("", DIAGONAL_BLANK),
+            None => ("", self.blank),
+            Some(digit) => {
+                let paint = if conflicting[row * size + col] {
+                    CONFLICT
+                } else if givens.get(row, col) == Some(digit) {
+                    GIVEN
+                } else {
+                    SOLVED
+                };
+                (paint, symbol(digit))
+            }
+        };
+        let content = content.to_string();
+        match (shaded, paint) {
+            (true, _) => self.push_paint(text, &format!("{}{}", SHADE, paint), &content),
+            (false, "") => text.push_str(&content),
+            (false, _) => self.push_paint(text, paint, &content),
+        }
+    }
+
+    /**
+     * Appends a horizontal line of the frame.
+     * @param position 0 for the top line, 1 between boxes, 2 for the bottom.
+     */
+    fn push_line(&self, text: &mut String, frame: &Frame, shape: Shape, position: usize) {
+        let [left, middle, right] = frame.joints[position];
+        let segment = frame.horizontal.repeat(2 * shape.box_cols() + 1);
+        let mut line = String::from(left);
+        for stack in 0..shape.box_rows() {
+            if stack > 0 {
+                line.push_str(middle);
+            }
+            line.push_str(&segment);
+        }
+        line.push_str(right);
+        self.push_paint(text, LINES, &line);
+        text.push('\n');
+    }
+
+    /**
+     * Appends text, wrapped in an ANSI color when colors are on.
+     */
+    fn push_paint(&self, text: &mut String, paint: &str, content: &str) {
+        if self.color {
+            text.push_str(paint);
+            text.push_str(content);
+            text.push_str(RESET);
+        } else {
+            text.push_str(content);
+        }
+    }
+}
@@ -0,0 +1,373 @@
+use crate::board::{digits, Board, Mask};
+use crate::constraint::Rules;
+use crate::grid::Grid;
+
+/**
+ * Backtracking Sudoku solver.
+ *
+ * The search always branches on the blank cell with the fewest candidates,
+ * under the constraints of its rules.
+ */
+#[derive(Debug, Default, Clone)]
+pub struct Solver {
+    rules: Rules,
+}
+
+impl Solver {
+    /**
+     * Creates a solver for the classic rules.
+     */
+    pub fn new() -> Self {
+        Solver::with_rules(Rules::classic())
+    }
+
+    /**
+     * Creates a solver for the rules of a variant.
+     */
+    pub fn with_rules(rules: Rules) -> Self {
+        Solver { rules }
+    }
+
+    /**
+     * Returns the rules the solver enforces.
+     */
+    pub fn rules(&self) -> &Rules {
+        &self.rules
+    }
+
+    /**
+     * Loads the givens of a grid under the solver's rules.
+     */
+    fn board(&self, grid: &Grid) -> Option<Board> {
+        Board::with_layout(grid, self.rules.layout(grid.shape()))
+    }
+
+    /**
+     * Solves a Sudoku grid.
+     * @param grid The puzzle to solve.
+     * @return The first solution found, or `None` if the puzzle has none.
+     */
+    pub fn solve(&self, grid: &Grid) -> Option<Grid> {
+        let mut board = self.board(grid)?;
+        if solve_recursive(&mut board) {
+            Some(board.to_grid())
+        } else {
+            None
+        }
+    }
+
+    /**
+     * Counts the solutions of a grid, stopping as soon as `limit` are found.
+     * @param grid The puzzle to count solutions of.
+     * @param limit The count at which to stop searching.
+     * @return The number of solutions, at most `limit`.
+     */
+    pub fn count_solutions(&self, grid: &Grid, limit: usize) -> usize {
+        self.solve_count(grid, limit).1
+    }
+
+    /**
+     * Solves a grid and counts its solutions in a single search, stopping as
+     * soon as `limit` are found.
+     * @param grid The puzzle to solve.
+     * @param limit The count at which to stop searching.
+     * @return The first solution found, if any, and the number of solutions,
+     * at most `limit`.
+     */
+    pub fn solve_count(&self, grid: &Grid, limit: usize) -> (Option<Grid>, usize) {
+        let mut first = None;
+        let mut count = 0;
+        if let Some(mut board) = self.board(grid) {
+            if limit > 0 {
+                count_recursive(&mut board, limit, &mut count, &mut first);
+            }
+        }
+        (first, count)
+    }
+
+    /**
+     * Enumerates the solutions of a grid lazily.
+     *
+     * The search runs one solution at a time on an explicit stack, so
+     * `take(n)` only does the work for `n` solutions and dropping the
+     * iterator abandons the rest of the search.
+     *
+     * @param grid The puzzle to solve.
+     * @return An iterator over every solution, in search order.
+     */
+    pub fn solutions(&self, grid: &Grid) -> Solutions {
+        Solutions::new(self.board(grid))
+    }
+
+    /**
+     * Counts every solution of a grid.
+     *
+     * This enumerates the whole search tree; prefer `count_solutions` with a
+     * limit or `has_unique_solution` when only a bound is needed.
+     */
+    pub fn count(&self, grid: &Grid) -> usize {
+        self.count_solutions(grid, usize::MAX)
+    }
+
+    /**
+     * Returns true if the grid has exactly one solution.
+     */
+    pub fn has_unique_solution(&self, grid: &Grid) -> bool {
+        self.count_solutions(grid, 2) == 1
+    }
+
+    /**
+     * Returns true if the grid has a unique solution and every given is
+     * needed for that: blanking any one of them admits a second solution.
+     */
+    pub fn is_minimal(&self, grid: &Grid) -> bool {
+        if !self.has_unique_solution(grid) {
+            return false;
+        }
+        let mut grid = grid.clone();
+        let size = grid.size();
+        for cell in 0..size * size {
+            let (row, col) = (cell / size, cell % size);
+            if let Some(value) = grid.get(row, col) {
+                grid.clear(row, col);
+                let redundant = self.has_unique_solution(&grid);
+                grid.set(row, col, value).expect("digit in range");
+                if redundant {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /**
+     * Removes redundant givens, in row-major order, until every remaining
+     * given is necessary.
+     *
+     * A given that is necessary stays necessary as others are removed, so a
+     * single pass is enough.
+     *
+     * @param grid A grid with a unique solution.
+     * @return A minimal grid with the same solution, or the grid unchanged
+     * if it does not have a unique solution.
+     */
+    pub fn minimize(&self, grid: &Grid) -> Grid {
+        let mut grid = grid.clone();
+        if !self.has_unique_solution(&grid) {
+            return grid;
+        }
+        let size = grid.size();
+        for cell in 0..size * size {
+            let (row, col) = (cell / size, cell % size);
+            if let Some(value) = grid.get(row, col) {
+                grid.clear(row, col);
+                if !self.has_unique_solution(&grid) {
+                    grid.set(row, col, value).expect("digit in range");
+                }
+            }
+        }
+        grid
+    }
+}
+
+fn solve_recursive(board: &mut Board) -> bool {
+    let Some((cell, candidates)) = board.most_constrained() else {
+        return true;
+    };
+    for digit in digits(candidates) {
+        board.place(cell, digit);
+        if solve_recursive(board) {
+            return true;
+        }
+        board.unplace(cell);
+    }
+    false
+}
+
+/**
+ * Counts the solutions of a board by backtracking on its most constrained
+ * cell, placing and undoing digits in place.
+ *
+ * @param board The search state (restored before returning)
+ * @param limit The count at which to stop searching
+ * @param count The count of valid solutions found (modified by reference)
+ * @param first The first solution found (set once it is found)
+ * @return True once `limit` solutions have been found.
+ */
+fn count_recursive(
+    board: &mut Board,
+    limit: usize,
+    count: &mut usize,
+    first: &mut Option<Grid>,
+) -> bool {
+    let Some((cell, candidates)) = board.most_constrained() else {
+        if first.is_none() {
+            *first = Some(board.to_grid());
+        }
+        *count += 1;
+        return *count >= limit;
+    };
+    for digit in digits(candidates) {
+        board.place(cell, digit);
+        let done = count_recursive(board, limit, count, first);
+        board.unplace(cell);
+        if done {
+            return true;
+        }
+    }
+    false
+}
+
+/**
+ * A branch point of the search: a cell and the candidates not yet tried.
+ */
+struct Frame {
+    cell: usize,
+    untried: Mask,
+    placed: bool,
+}
+
+/**
+ * Iterator over the solutions of a grid, returned by `Solver::solutions`.
+ */
+pub struct Solutions {
+    /** The search state, `None` once the search is over. */
+    board: Option<Board>,
+    stack: Vec<Frame>,
+    /** A grid that was already full, yielded before any search. */
+    full: Option<Grid>,
+    /** The digits the search may still place before giving up. */
+    budget: usize,
+    ran_out: bool,
+}
+
+impl Solutions {
+    fn new(board: Option<Board>) -> Self {
+        let mut solutions = Solutions {
+            board,
+            stack: Vec::new(),
+            full: None,
+            budget: usize::MAX,
+            ran_out: false,
+        };
+        if let Some(board) = &solutions.board {
+            match board.most_constrained() {
+                Some((cell, untried)) => solutions.stack.push(Frame {
+                    cell,
+                    untried,
+                    placed: false,
+                }),
+                None => solutions.full = Some(board.to_grid()),
+            }
+        }
+        solutions
+    }
+
+    /**
+     * Limits the search to placing `placements` digits, after which it ends
+     * as if there were no more solutions.
+     */
+    pub(crate) fn within(mut self, placements: usize) -> Self {
+        self.budget = placements;
+        self
+    }
+
+    /**
+     * Returns true if the search ended because its budget ran out rather
+     * than because every solution was found.
+     */
+    pub(crate) fn ran_out(&self) -> bool {
+        self.ran_out
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Grid;
+
+    fn next(&mut self) -> Option<Grid> {
+        if let Some(grid) = self.full.take() {
+            self.board = None;
+            return Some(grid);
+        }
+        let board = self.board.as_mut()?;
+        loop {
+            let Some(frame) = self.stack.last_mut() else {
+                self.board = None;
+                return None;
+            };
+            if frame.placed {
+                board.unplace(frame.cell);
+                frame.placed = false;
+            }
+            let Some(digit) = digits(frame.untried).next() else {
+                self.stack.pop();
+                continue;
+            };
+            if self.budget == 0 {
+                self.board = None;
+                self.ran_out = true;
+                return None;
+            }
+            self.budget -= 1;
+            frame.untried &= frame.untried - 1;
+            board.place(frame.cell, digit);
+            frame.placed = true;
+            match board.most_constrained() {
+                Some((cell, untried)) => self.stack.push(Frame {
+                    cell,
+                    untried,
+                    placed: false,
+                }),
+                None => return Some(board.to_grid()),
+            }
+        }
+    }
+}
+
+impl std::iter::FusedIterator for Solutions {}
+
+/**
+ * Enumerates the solutions of a grid lazily.
+ *
+ * Shorthand for `Solver::new().solutions(grid)`.
+ */
+pub fn solutions(grid: &Grid) -> Solutions {
+    Solver::new().solutions(grid)
+}
+
+/**
+ * Counts the solutions of a grid, stopping as soon as `limit` are found.
+ *
+ * Shorthand for `Solver::new().count_solutions(grid, limit)`.
+ */
+pub fn count_solutions(grid: &Grid, limit: usize) -> usize {
+    Solver::new().count_solutions(grid, limit)
+}
+
+/**
+ * Returns true if the grid has exactly one solution.
+ *
+ * Shorthand for `Solver::new().has_unique_solution(grid)`.
+ */
+pub fn has_unique_solution(grid: &Grid) -> bool {
+    Solver::new().has_unique_solution(grid)
+}
+
+/**
+ * Returns true if the grid has a unique solution and none of its givens can
+ * be removed without losing that.
+ *
+ * Shorthand for `Solver::new().is_minimal(grid)`.
+ */
+pub fn is_minimal(grid: &Grid) -> bool {
+    Solver::new().is_minimal(grid)
+}
+
+/**
+ * Removes redundant givens until the grid is minimal.
+ *
+ * Shorthand for `Solver::new().minimize(grid)`.
+ */
+pub fn minimize(grid: &Grid) -> Grid {
+    Solver::new().minimize(grid)
+}
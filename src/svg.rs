@@ -0,0 +1,356 @@
+use std::fmt::Write;
+
+use crate::draw::{border_width, draw_grid, number, Canvas, Ink, Overlay};
+use crate::grid::Grid;
+use crate::jigsaw::Regions;
+use crate::killer::{Cage, Cages};
+use crate::logic::Candidates;
+
+/**
+ * Draws grids as standalone SVG documents for print.
+ *
+ * Box borders are thick, givens bold and solved digits in their own color.
+ * Blank cells can show their candidates as small pencil marks.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgRenderer {
+    cell_size: f64,
+    font_family: String,
+    pencil_marks: bool,
+    diagonals: bool,
+    cages: Vec<Cage>,
+    regions: Option<Regions>,
+    background: String,
+    line_color: String,
+    given_color: String,
+    solved_color: String,
+    mark_color: String,
+    shade_color: String,
+    cage_color: String,
+}
+
+impl Default for SvgRenderer {
+    fn default() -> Self {
+        SvgRenderer::new()
+    }
+}
+
+impl SvgRenderer {
+    /**
+     * Creates a renderer with 40-unit cells, a sans-serif font, black lines
+     * and givens, blue solved digits, no pencil marks and no shading.
+     */
+    pub fn new() -> Self {
+        SvgRenderer {
+            cell_size: 40.0,
+            font_family: "sans-serif".to_string(),
+            pencil_marks: false,
+            diagonals: false,
+            cages: Vec::new(),
+            regions: None,
+            background: "white".to_string(),
+            line_color: "black".to_string(),
+            given_color: "black".to_string(),
+            solved_color: "#1f5fbf".to_string(),
+            mark_color: "#707070".to_string(),
+            shade_color: "#e4e4e4".to_string(),
+            cage_color: "#404040".to_string(),
+        }
+    }
+
+    /**
+     * Sets the side of a cell in SVG user units; everything else scales with
+     * it.
+     */
+    pub fn cell_size(mut self, cell_size: f64) -> Self {
+        self.cell_size = cell_size;
+        self
+    }
+
+    /**
+     * Sets the font family of digits and pencil marks, as a CSS font list.
+     */
+    pub fn font_family(mut self, font_family: &str) -> Self {
+        self.font_family = font_family.to_string();
+        self
+    }
+
+    /**
+     * Shows the candidates of blank cells in small type.
+     */
+    pub fn pencil_marks(mut self, pencil_marks: bool) -> Self {
+        self.pencil_marks = pencil_marks;
+        self
+    }
+
+    /**
+     * Shades the cells of both main diagonals, for Sudoku-X puzzles.
+     */
+    pub fn diagonals(mut self, diagonals: bool) -> Self {
+        self.diagonals = diagonals;
+        self
+    }
+
+    /**
+     * Outlines the cages of a Killer Sudoku with dashed lines and writes
+     * their sums in their top-left corners.
+     */
+    pub fn cages(mut self, cages: &Cages) -> Self {
+        self.cages = cages.cages().to_vec();
+        self
+    }
+
+    /**
+     * Draws the thick borders along the regions of a Jigsaw Sudoku instead
+     * of the boxes. Pencil marks follow the boxes, so they are left out.
+     * Drawing a grid of another size then panics.
+     */
+    pub fn regions(mut self, regions: &Regions) -> Self {
+        self.regions = Some(regions.clone());
+        self
+    }
+
+    /**
+     * Sets the fill behind the grid; `none` leaves it transparent.
+     */
+    pub fn background(mut self, color: &str) -> Self {
+        self.background = color.to_string();
+        self
+    }
+
+    /**
+     * Sets the color of the grid lines.
+     */
+    pub fn line_color(mut self, color: &str) -> Self {
+        self.line_color = color.to_string();
+        self
+    }
+
+    /**
+     * Sets the color of givens.
+     */
+    pub fn given_color(mut self, color: &str) -> Self {
+        self.given_color = color.to_string();
+        self
+    }
+
+    /**
+     * Sets the color of digits filled in by solving.
+     */
+    pub fn solved_color(mut self, color: &str) -> Self {
+        self.solved_color = color.to_string();
+        self
+    }
+
+    /**
+     * Sets the color of pencil marks.
+     */
+    pub fn mark_color(mut self, color: &str) -> Self {
+        self.mark_color = color.to_string();
+        self
+    }
+
+    /**
+     * Sets the fill of shaded cells.
+     */
+    pub fn shade_color(mut self, color: &str) -> Self {
+        self.shade_color = color.to_string();
+        self
+    }
+
+    /**
+     * Sets the color of cage outlines and sums.
+     */
+    pub fn cage_color(mut self, color: &str) -> Self {
+        self.cage_color = color.to_string();
+        self
+    }
+
+    /**
+     * Draws a grid, treating every digit as a given.
+     * @return The SVG document.
+     */
+    pub fn render(&self, grid: &Grid) -> String {
+        self.render_solved(grid, grid)
+    }
+
+    /**
+     * Draws a grid filled in from a puzzle, showing the digits that are not
+     * among the givens as solved. Pencil marks, when on, are the digits not
+     * yet used in a blank cell's row, column or box.
+     * @param givens The clues of the puzzle.
+     * @param grid The grid to draw, normally the givens plus placed digits.
+     * @return The SVG document.
+     */
+    pub fn render_solved(&self, givens: &Grid, grid: &Grid) -> String {
+        let marks = if self.pencil_marks && self.regions.is_none() {
+            Candidates::new(grid)
+        } else {
+            None
+        };
+        self.draw(givens, grid, marks.as_ref())
+    }
+
+    /**
+     * Draws the state of a logical solve: placed digits, and the remaining
+     * candidates of blank cells as pencil marks.
+     * @param givens The clues of the puzzle.
+     * @param candidates The solve state.
+     * @return The SVG document.
+     */
+    pub fn render_candidates(&self, givens: &Grid, candidates: &Candidates) -> String {
+        self.draw(givens, &candidates.to_grid(), Some(candidates))
+    }
+
+    fn draw(&self, givens: &Grid, grid: &Grid, marks: Option<&Candidates>) -> String {
+        if let Some(regions) = &self.regions {
+            assert_eq!(
+                regions.shape().size(),
+                grid.size(),
+                "the regions are for another grid size"
+            );
+        }
+        let margin = border_width(self.cell_size);
+        let side = number(grid.size() as f64 * self.cell_size + 2.0 * margin);
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{0}\" \
+             viewBox=\"0 0 {0} {0}\">",
+            side
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<rect width=\"{0}\" height=\"{0}\" fill=\"{1}\"/>",
+            side,
+            escape(&self.background)
+        )
+        .unwrap();
+        writeln!(
+            svg,
+            "<g font-family=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">",
+            escape(&self.font_family)
+        )
+        .unwrap();
+        let mut canvas = SvgCanvas {
+            renderer: self,
+            svg,
+        };
+        draw_grid(
+            &mut canvas,
+            (margin, margin),
+            self.cell_size,
+            givens,
+            grid,
+            marks,
+            Overlay {
+                diagonals: self.diagonals,
+                cages: &self.cages,
+                regions: self.regions.as_ref(),
+            },
+        );
+        let mut svg = canvas.svg;
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    fn color(&self, ink: Ink) -> &str {
+        match ink {
+            Ink::Line => &self.line_color,
+            Ink::Given => &self.given_color,
+            Ink::Solved => &self.solved_color,
+            Ink::Mark => &self.mark_color,
+            Ink::Shade => &self.shade_color,
+            Ink::Cage => &self.cage_color,
+            Ink::Paper => &self.background,
+        }
+    }
+}
+
+/**
+ * Appends SVG elements for the shared grid drawing.
+ */
+struct SvgCanvas<'a> {
+    renderer: &'a SvgRenderer,
+    svg: String,
+}
+
+impl SvgCanvas<'_> {
+    /**
+     * Appends one path of straight segments.
+     * @param attributes Extra attributes, each with a leading space.
+     */
+    fn path(&mut self, segments: &[(f64, f64, f64, f64)], width: f64, ink: Ink, attributes: &str) {
+        let mut path = String::new();
+        for &(x1, y1, x2, y2) in segments {
+            write!(
+                path,
+                "M{} {}L{} {}",
+                number(x1),
+                number(y1),
+                number(x2),
+                number(y2)
+            )
+            .unwrap();
+        }
+        writeln!(
+            self.svg,
+            "<path stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"square\"{} \
+             fill=\"none\" d=\"{}\"/>",
+            escape(self.renderer.color(ink)),
+            number(width),
+            attributes,
+            path
+        )
+        .unwrap();
+    }
+}
+
+impl Canvas for SvgCanvas<'_> {
+    fn lines(&mut self, segments: &[(f64, f64, f64, f64)], width: f64, ink: Ink) {
+        self.path(segments, width, ink, "");
+    }
+
+    fn dashed_lines(&mut self, segments: &[(f64, f64, f64, f64)], width: f64, dash: f64, ink: Ink) {
+        let dashes = format!(" stroke-dasharray=\"{}\"", number(dash));
+        self.path(segments, width, ink, &dashes);
+    }
+
+    fn rect(&mut self, x: f64, y: f64, width: f64, height: f64, ink: Ink) {
+        writeln!(
+            self.svg,
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+            number(x),
+            number(y),
+            number(width),
+            number(height),
+            escape(self.renderer.color(ink))
+        )
+        .unwrap();
+    }
+
+    fn text(&mut self, x: f64, y: f64, size: f64, bold: bool, ink: Ink, text: &str) {
+        let weight = if bold { " font-weight=\"bold\"" } else { "" };
+        writeln!(
+            self.svg,
+            "<text x=\"{}\" y=\"{}\" font-size=\"{}\"{} fill=\"{}\">{}</text>",
+            number(x),
+            number(y),
+            number(size),
+            weight,
+            escape(self.renderer.color(ink)),
+            text
+        )
+        .unwrap();
+    }
+}
+
+/**
+ * Escapes text for use inside an attribute value.
+ */
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+}
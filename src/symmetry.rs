@@ -0,0 +1,170 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::grid::SIZE;
+
+/**
+ * A symmetry of the clue pattern. Clues are removed a whole orbit at a time,
+ * so the givens of a puzzle stay invariant under the chosen symmetry.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    /** Cells are removed one at a time. */
+    #[default]
+    None,
+    /** Invariant under a half turn about the centre. */
+    Rotational180,
+    /** Invariant under a quarter turn about the centre. */
+    Rotational90,
+    /** Mirrored across the horizontal centre line (top and bottom match). */
+    MirrorHorizontal,
+    /** Mirrored across the vertical centre line (left and right match). */
+    MirrorVertical,
+    /** Mirrored across the main diagonal. */
+    Diagonal,
+    /** Invariant under every rotation and reflection of the square. */
+    Dihedral,
+}
+
+impl Symmetry {
+    /** Every symmetry. */
+    pub const ALL: [Symmetry; 7] = [
+        Symmetry::None,
+        Symmetry::Rotational180,
+        Symmetry::Rotational90,
+        Symmetry::MirrorHorizontal,
+        Symmetry::MirrorVertical,
+        Symmetry::Diagonal,
+        Symmetry::Dihedral,
+    ];
+
+    /**
+     * Returns the short name used on the command line.
+     */
+    pub fn name(self) -> &'static str {
+        match self {
+            Symmetry::None => "none",
+            Symmetry::Rotational180 => "rotational",
+            Symmetry::Rotational90 => "rotational90",
+            Symmetry::MirrorHorizontal => "horizontal",
+            Symmetry::MirrorVertical => "vertical",
+            Symmetry::Diagonal => "diagonal",
+            Symmetry::Dihedral => "dihedral",
+        }
+    }
+}
+
+impl fmt::Display for Symmetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Symmetry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "180" | "rotational180" => Ok(Symmetry::Rotational180),
+            "90" => Ok(Symmetry::Rotational90),
+            name => Symmetry::ALL
+                .into_iter()
+                .find(|symmetry| symmetry.name() == name)
+                .ok_or_else(|| format!("unknown symmetry '{}'", s)),
+        }
+    }
+}
+
+/** Maps a cell to its image, given the index of the last row and column. */
+type Transform = fn(usize, usize, usize) -> (usize, usize);
+
+const IDENTITY: Transform = |row, col, _| (row, col);
+const ROTATE_90: Transform = |row, col, last| (col, last - row);
+const ROTATE_180: Transform = |row, col, last| (last - row, last - col);
+const ROTATE_270: Transform = |row, col, last| (last - col, row);
+const FLIP_ROWS: Transform = |row, col, last| (last - row, col);
+const FLIP_COLS: Transform = |row, col, last| (row, last - col);
+const TRANSPOSE: Transform = |row, col, _| (col, row);
+const ANTI_TRANSPOSE: Transform = |row, col, last| (last - col, last - row);
+
+impl Symmetry {
+    /**
+     * Returns every transformation in the symmetry group, identity included.
+     */
+    fn group(self) -> &'static [Transform] {
+        match self {
+            Symmetry::None => &[IDENTITY],
+            Symmetry::Rotational180 => &[IDENTITY, ROTATE_180],
+            Symmetry::Rotational90 => &[IDENTITY, ROTATE_90, ROTATE_180, ROTATE_270],
+            Symmetry::MirrorHorizontal => &[IDENTITY, FLIP_ROWS],
+            Symmetry::MirrorVertical => &[IDENTITY, FLIP_COLS],
+            Symmetry::Diagonal => &[IDENTITY, TRANSPOSE],
+            Symmetry::Dihedral => &[
+                IDENTITY,
+                ROTATE_90,
+                ROTATE_180,
+                ROTATE_270,
+                FLIP_ROWS,
+                FLIP_COLS,
+                TRANSPOSE,
+                ANTI_TRANSPOSE,
+            ],
+        }
+    }
+
+    /**
+     * Returns the cells a given cell is mapped to by the symmetry.
+     * @param row The row of the cell.
+     * @param col The column of the cell.
+     * @return The distinct cells of the orbit, starting with the cell itself.
+     */
+    pub fn orbit(self, row: usize, col: usize) -> Vec<(usize, usize)> {
+        self.orbit_in(SIZE, row, col)
+    }
+
+    /**
+     * Returns the orbit of a cell, as `orbit` does, in a grid of any size.
+     * @param size The number of rows and columns of the grid.
+     */
+    pub fn orbit_in(self, size: usize, row: usize, col: usize) -> Vec<(usize, usize)> {
+        let mut orbit = Vec::new();
+        for transform in self.group() {
+            let cell = transform(row, col, size - 1);
+            if !orbit.contains(&cell) {
+                orbit.push(cell);
+            }
+        }
+        orbit
+    }
+
+    /**
+     * Splits the grid into the orbits of the symmetry.
+     * @return Every orbit exactly once, in row-major order of their first cell.
+     */
+    pub fn orbits(self) -> Vec<Vec<(usize, usize)>> {
+        self.orbits_in(SIZE)
+    }
+
+    /**
+     * Splits a grid of any size into the orbits of the symmetry, as `orbits`
+     * does.
+     * @param size The number of rows and columns of the grid.
+     */
+    pub fn orbits_in(self, size: usize) -> Vec<Vec<(usize, usize)>> {
+        let mut seen = vec![vec![false; size]; size];
+        let mut orbits = Vec::new();
+        for row in 0..size {
+            for col in 0..size {
+                if seen[row][col] {
+                    continue;
+                }
+                let orbit = self.orbit_in(size, row, col);
+                for &(r, c) in &orbit {
+                    seen[r][c] = true;
+                }
+                orbits.push(orbit);
+            }
+        }
+        orbits
+    }
+}
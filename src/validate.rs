@@ -0,0 +1,299 @@
+use std::fmt;
+
+use crate::constraint::Rules;
+use crate::grid::{symbol, Grid, Shape};
+use crate::solver::Solver;
+
+/**
+ * A group of cells whose digits must differ, numbered from 0 in the order
+ * its constraint lists them: rows, columns and boxes in row-major order.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Row(usize),
+    Column(usize),
+    Box(usize),
+    /** The diagonal from the top left, then the one from the top right. */
+    Diagonal(usize),
+    /** A region of a jigsaw. */
+    Region(usize),
+    /** A killer cage, ordered by its first cell. */
+    Cage(usize),
+    /** A group of another constraint, named after it. */
+    Other {
+        rule: String,
+        index: usize,
+    },
+}
+
+impl Unit {
+    /**
+     * Names a group of a constraint by the constraint's name.
+     */
+    fn of(rule: &str, index: usize) -> Self {
+        match rule {
+            "rows" => Unit::Row(index),
+            "columns" => Unit::Column(index),
+            "boxes" => Unit::Box(index),
+            "diagonals" => Unit::Diagonal(index),
+            "regions" => Unit::Region(index),
+            "cages" => Unit::Cage(index),
+            _ => Unit::Other {
+                rule: rule.to_string(),
+                index,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Row(index) => write!(f, "row {}", index + 1),
+            Unit::Column(index) => write!(f, "column {}", index + 1),
+            Unit::Box(index) => write!(f, "box {}", index + 1),
+            Unit::Diagonal(index) => write!(f, "diagonal {}", index + 1),
+            Unit::Region(index) => write!(f, "region {}", index + 1),
+            Unit::Cage(index) => write!(f, "cage {}", index + 1),
+            Unit::Other { rule, index } => write!(f, "{} {}", rule, index + 1),
+        }
+    }
+}
+
+/**
+ * A broken rule of a grid. Rows and columns count from 0.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /** A digit appears more than once in a unit, at every one of `cells`. */
+    Duplicate {
+        digit: u8,
+        unit: Unit,
+        cells: Vec<(usize, usize)>,
+    },
+    /** A constraint broken other than by a repeated digit, such as a cage sum. */
+    Unsatisfied { rule: String },
+    /** A cell value outside `0..=size`, where `0` is a blank. */
+    OutOfRange {
+        row: usize,
+        col: usize,
+        value: u8,
+        size: usize,
+    },
+    /** The input does not have a row per digit. */
+    RowCount { expected: usize, found: usize },
+    /** A row does not have a cell per digit. */
+    RowLength {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Duplicate { digit, unit, cells } => {
+                let digit = symbol(*digit);
+                write!(f, "{} appears {} times in {} at", digit, cells.len(), unit)?;
+                for (i, (row, col)) in cells.iter().enumerate() {
+                    let separator = if i == 0 { " " } else { ", " };
+                    write!(f, "{}r{}c{}", separator, row + 1, col + 1)?;
+                }
+                Ok(())
+            }
+            Violation::Unsatisfied { rule } => write!(f, "the {} rule is broken", rule),
+            Violation::OutOfRange {
+                row,
+                col,
+                value,
+                size,
+            } => write!(
+                f,
+                "value {} at r{}c{} is outside 0..={}",
+                value,
+                row + 1,
+                col + 1,
+                size
+            ),
+            Violation::RowCount { expected, found } => {
+                write!(f, "expected {} rows, found {}", expected, found)
+            }
+            Violation::RowLength {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} cells, expected {}",
+                row + 1,
+                found,
+                expected
+            ),
+        }
+    }
+}
+
+/**
+ * How many solutions a grid has.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SolutionCount {
+    Zero,
+    One,
+    Multiple,
+}
+
+impl fmt::Display for SolutionCount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SolutionCount::Zero => "no solution",
+            SolutionCount::One => "one solution",
+            SolutionCount::Multiple => "more than one solution",
+        })
+    }
+}
+
+/**
+ * Everything wrong with a grid, and how many solutions it has.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validation {
+    /** Every broken rule, shape and range problems first. */
+    pub violations: Vec<Violation>,
+    /**
+     * The number of solutions, `None` if the input was not a grid of the
+     * expected shape with values in range.
+     */
+    pub solutions: Option<SolutionCount>,
+}
+
+impl Validation {
+    /**
+     * Returns true if no rule is broken and the grid has exactly one
+     * solution.
+     */
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty() && self.solutions == Some(SolutionCount::One)
+    }
+}
+
+/**
+ * Checks a grid against the classic rules and counts its solutions.
+ * @param grid The grid to check.
+ * @return Every digit repeated in a row, column or box, and whether the grid
+ * has zero, one or several solutions.
+ */
+pub fn validate(grid: &Grid) -> Validation {
+    validate_with(grid, &Rules::classic())
+}
+
+/**
+ * Checks a grid against the rules of a variant and counts its solutions
+ * under them.
+ * @param grid The grid to check.
+ * @param rules The rules the grid follows, such as `Rules::diagonal()`.
+ * @return Every digit repeated in a group of cells of a constraint, every
+ * constraint broken otherwise, and whether the grid has zero, one or
+ * several solutions.
+ */
+pub fn validate_with(grid: &Grid, rules: &Rules) -> Validation {
+    let solutions = match Solver::with_rules(rules.clone()).count_solutions(grid, 2) {
+        0 => SolutionCount::Zero,
+        1 => SolutionCount::One,
+        _ => SolutionCount::Multiple,
+    };
+    Validation {
+        violations: violations_of(grid, rules),
+        solutions: Some(solutions),
+    }
+}
+
+/**
+ * Checks raw user input that may not even be a grid: rows of values where
+ * `0` is a blank.
+ *
+ * Shape and range problems are reported alongside the duplicates among the
+ * cells that are in range and inside the area of the grid; solutions are
+ * only counted when the input is a proper grid.
+ *
+ * @param shape The shape the input should have, such as `Shape::CLASSIC`.
+ * @param rows The rows of the input, each a sequence of cell values.
+ * @return Every problem with the input.
+ */
+pub fn validate_rows<R: AsRef<[u8]>>(shape: Shape, rows: &[R]) -> Validation {
+    let size = shape.size();
+    let mut violations = Vec::new();
+    if rows.len() != size {
+        violations.push(Violation::RowCount {
+            expected: size,
+            found: rows.len(),
+        });
+    }
+    let mut grid = Grid::new(shape);
+    for (row, values) in rows.iter().enumerate() {
+        let values = values.as_ref();
+        if values.len() != size {
+            violations.push(Violation::RowLength {
+                row,
+                expected: size,
+                found: values.len(),
+            });
+        }
+        for (col, &value) in values.iter().enumerate() {
+            if usize::from(value) > size {
+                violations.push(Violation::OutOfRange {
+                    row,
+                    col,
+                    value,
+                    size,
+                });
+            } else if value != 0 && row < size && col < size {
+                grid.set(row, col, value).expect("digit in range");
+            }
+        }
+    }
+    if violations.is_empty() {
+        return validate(&grid);
+    }
+    violations.extend(violations_of(&grid, &Rules::classic()));
+    Validation {
+        violations,
+        solutions: None,
+    }
+}
+
+/**
+ * Finds every digit repeated within a group of cells, constraint by
+ * constraint in the order of the rules, and every constraint broken some
+ * other way.
+ */
+fn violations_of(grid: &Grid, rules: &Rules) -> Vec<Violation> {
+    let size = grid.size();
+    let mut violations = Vec::new();
+    for constraint in rules.constraints() {
+        let found = violations.len();
+        let groups = constraint.regions(grid.shape());
+        for (index, group) in groups.iter().enumerate() {
+            let mut cells: Vec<Vec<(usize, usize)>> = vec![Vec::new(); size];
+            for &cell in group {
+                let (row, col) = (cell / size, cell % size);
+                if let Some(digit) = grid.get(row, col) {
+                    cells[usize::from(digit) - 1].push((row, col));
+                }
+            }
+            for (digit, cells) in (1..).zip(cells) {
+                if cells.len() > 1 {
+                    let unit = Unit::of(constraint.name(), index);
+                    violations.push(Violation::Duplicate { digit, unit, cells });
+                }
+            }
+        }
+        if violations.len() == found && !constraint.is_satisfied(grid) {
+            violations.push(Violation::Unsatisfied {
+                rule: constraint.name().to_string(),
+            });
+        }
+    }
+    violations
+}
@@ -0,0 +1,122 @@
+use sudoku::{Booklet, BookletOptions, GenerationError, Generator, PageSize, Shape, Tier};
+
+/**
+ * Checks the cross-reference table: every offset must point at the start of
+ * its object, and the trailer must point at the table.
+ */
+fn check_structure(pdf: &str) {
+    assert!(pdf.starts_with("%PDF-1.4\n"));
+    assert!(pdf.ends_with("%%EOF\n"));
+    let startxref: usize = pdf
+        .rsplit("startxref\n")
+        .next()
+        .unwrap()
+        .lines()
+        .next()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(pdf[startxref..].starts_with("xref\n"));
+    let entries: Vec<&str> = pdf[startxref..].lines().skip(3).collect();
+    let objects = entries
+        .iter()
+        .take_while(|line| line.ends_with(" n "))
+        .count();
+    assert!(objects > 5);
+    for (index, entry) in entries[..objects].iter().enumerate() {
+        let offset: usize = entry[..10].parse().unwrap();
+        assert!(pdf[offset..].starts_with(&format!("{} 0 obj\n", index + 1)));
+    }
+    for stream in pdf.split("<< /Length ").skip(1) {
+        let (length, rest) = stream.split_once(" >>\nstream\n").unwrap();
+        let length: usize = length.parse().unwrap();
+        assert!(rest[length..].starts_with("endstream"));
+    }
+}
+
+#[test]
+fn puzzles_are_split_across_tiers_easiest_first() {
+    let options = BookletOptions::new(5)
+        .tiers(&[Tier::Medium, Tier::Easy])
+        .per_page(2);
+    let booklet = Booklet::generate(&mut Generator::from_seed(18), &options).unwrap();
+    let tiers: Vec<Tier> = booklet.puzzles().iter().map(|(tier, _)| *tier).collect();
+    assert_eq!(
+        tiers,
+        [
+            Tier::Easy,
+            Tier::Easy,
+            Tier::Easy,
+            Tier::Medium,
+            Tier::Medium
+        ]
+    );
+    for (tier, puzzle) in booklet.puzzles() {
+        assert_eq!(sudoku::rate(puzzle.givens()).unwrap().tier, *tier);
+    }
+    assert_eq!(booklet.pages(), 4);
+}
+
+#[test]
+fn pdf_has_every_page_label_and_answer() {
+    let options = BookletOptions::new(6)
+        .tiers(&[Tier::Easy, Tier::Hard])
+        .per_page(4)
+        .page_size(PageSize::Letter)
+        .title("Puzzles (vol. 1)");
+    let booklet = Booklet::generate(&mut Generator::from_seed(19), &options).unwrap();
+    let pdf = String::from_utf8(booklet.to_pdf()).unwrap();
+    check_structure(&pdf);
+    assert!(pdf.contains("/Count 3 >>"));
+    assert_eq!(pdf.matches("/MediaBox [0 0 612 792]").count(), 3);
+    assert!(pdf.contains("(Puzzles \\(vol. 1\\))"));
+    for page in 1..=3 {
+        assert!(pdf.contains(&format!("({} / 3)", page)));
+    }
+    for index in 1..=6 {
+        assert!(pdf.contains(&format!("(Puzzle {})", index)));
+    }
+    assert_eq!(pdf.matches("(Hard)").count(), 3);
+    assert!(pdf.contains("(Answers)"));
+    assert!(pdf.contains("(6 \\(Hard\\))"));
+}
+
+#[test]
+fn empty_booklets_are_errors() {
+    let mut generator = Generator::from_seed(20);
+    for options in [BookletOptions::new(0), BookletOptions::new(4).tiers(&[])] {
+        assert_eq!(
+            Booklet::generate(&mut generator, &options).unwrap_err(),
+            GenerationError::EmptyBooklet
+        );
+    }
+}
+
+#[test]
+fn crowded_pages_are_errors() {
+    let mut generator = Generator::from_seed(21);
+    let shape = Shape::new(2, 2).unwrap();
+    let crowded = BookletOptions::new(2)
+        .shape(shape)
+        .tiers(&[Tier::Easy])
+        .per_page(60);
+    let Err(GenerationError::CrowdedPage { per_page: 60, max }) =
+        Booklet::generate(&mut generator, &crowded)
+    else {
+        panic!("60 puzzles fit on a page");
+    };
+    assert!((4..60).contains(&max));
+    let full = crowded.clone().per_page(max + 1);
+    assert!(Booklet::generate(&mut generator, &full).is_err());
+
+    let booklet = Booklet::generate(&mut generator, &full.per_page(max)).unwrap();
+    let pdf = String::from_utf8(booklet.to_pdf()).unwrap();
+    check_structure(&pdf);
+    let sizes: Vec<f64> = pdf
+        .split(" Tf ")
+        .map(|before| before.rsplit(' ').next().unwrap())
+        .filter_map(|size| size.parse().ok())
+        .collect();
+    assert!(sizes.len() > 4);
+    assert!(sizes.iter().all(|&size| size >= 5.0), "{:?}", sizes);
+}
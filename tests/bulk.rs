@@ -0,0 +1,85 @@
+use std::io::Cursor;
+
+use sudoku::{BulkSolver, Format, Generator, Outcome, ParseErrorKind, PuzzleOptions, Solver};
+
+#[test]
+fn reports_come_back_in_input_order() {
+    let mut generator = Generator::from_seed(15);
+    let puzzles: Vec<_> = (0..50)
+        .map(|_| generator.generate(&PuzzleOptions::new(30)).unwrap())
+        .collect();
+    let mut text = String::from("# benchmark\n");
+    for puzzle in &puzzles {
+        text.push_str(&Format::Line.write(puzzle.givens()));
+    }
+
+    let mut reports = Vec::new();
+    let summary = BulkSolver::new()
+        .threads(4)
+        .run(Cursor::new(text), |report| reports.push(report.clone()))
+        .unwrap();
+    assert_eq!(summary.puzzles, 50);
+    assert_eq!(summary.unique, 50);
+    assert_eq!(summary.failures(), 0);
+    assert_eq!(reports.len(), 50);
+    for (index, (report, puzzle)) in reports.iter().zip(&puzzles).enumerate() {
+        assert_eq!(report.line, index + 2);
+        assert_eq!(
+            report.outcome,
+            Outcome::Solved {
+                solution: puzzle.solution().clone(),
+                solutions: 1,
+            }
+        );
+    }
+}
+
+#[test]
+fn failures_are_counted_by_kind() {
+    let empty = ".".repeat(81);
+    let conflict = format!("11{}", ".".repeat(79));
+    let text = format!("{}\n\n{}\n12x\n", empty, conflict);
+
+    let mut reports = Vec::new();
+    let summary = BulkSolver::new()
+        .threads(2)
+        .limit(5)
+        .run(Cursor::new(text), |report| reports.push(report.clone()))
+        .unwrap();
+    assert_eq!(
+        (summary.multiple, summary.unsolvable, summary.malformed),
+        (1, 1, 1)
+    );
+    assert_eq!(summary.failures(), 3);
+    match &reports[0].outcome {
+        Outcome::Solved {
+            solution,
+            solutions,
+        } => {
+            assert_eq!(*solutions, 5);
+            assert!(Solver::new().solve(solution).is_some());
+        }
+        outcome => panic!("unexpected {:?}", outcome),
+    }
+    assert_eq!(reports[1].line, 3);
+    match &reports[2].outcome {
+        Outcome::Malformed(error) => {
+            assert_eq!(error.kind, ParseErrorKind::InvalidCharacter('x'));
+            assert_eq!((error.line, error.column), (4, 3));
+        }
+        outcome => panic!("unexpected {:?}", outcome),
+    }
+}
+
+#[test]
+fn limits_still_tell_unique_puzzles_apart() {
+    let empty = ".".repeat(81);
+    let summary = BulkSolver::new()
+        .limit(1)
+        .run(Cursor::new(empty), |report| match &report.outcome {
+            Outcome::Solved { solutions, .. } => assert_eq!(*solutions, 2),
+            outcome => panic!("unexpected {:?}", outcome),
+        })
+        .unwrap();
+    assert_eq!((summary.unique, summary.multiple), (0, 1));
+}
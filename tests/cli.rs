@@ -0,0 +1,321 @@
+use std::io::{ErrorKind, Write};
+use std::process::{Command, Output, Stdio};
+
+fn sudoku(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_sudoku"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let written = child.stdin.take().unwrap().write_all(stdin.as_bytes());
+    // Commands that reject their arguments exit without reading the input.
+    if let Err(error) = written {
+        assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+    }
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn generated_puzzles_solve_and_validate() {
+    let generated = sudoku(&["generate", "--count", "2", "--seed", "1"], "");
+    assert!(generated.status.success());
+    let puzzles = String::from_utf8(generated.stdout).unwrap();
+    assert_eq!(puzzles.lines().count(), 2);
+
+    let validated = sudoku(&["validate"], &puzzles);
+    assert!(validated.status.success());
+    assert_eq!(String::from_utf8_lossy(&validated.stdout), "valid\nvalid\n");
+
+    let solved = sudoku(&["solve"], &puzzles);
+    assert!(solved.status.success());
+    for line in String::from_utf8(solved.stdout).unwrap().lines() {
+        assert_eq!(line.len(), 81);
+        assert!(!line.contains('.'));
+    }
+}
+
+#[test]
+fn exit_codes_distinguish_failures() {
+    assert_eq!(sudoku(&[], "").status.code(), Some(2));
+    assert_eq!(sudoku(&["generate", "--bogus"], "").status.code(), Some(2));
+    assert_eq!(sudoku(&["validate"], "123\n").status.code(), Some(1));
+    assert_eq!(
+        sudoku(&["rate", "-i", "/no/such/file"], "").status.code(),
+        Some(3)
+    );
+    assert_eq!(sudoku(&["count", "--help"], "").status.code(), Some(0));
+}
+
+#[test]
+fn bulk_prints_one_line_per_puzzle() {
+    let generated = sudoku(&["generate", "--count", "3", "--seed", "2"], "");
+    let puzzles = String::from_utf8(generated.stdout).unwrap();
+
+    let solved = sudoku(&["bulk", "--threads", "2"], &puzzles);
+    assert!(solved.status.success());
+    let stdout = String::from_utf8(solved.stdout).unwrap();
+    let lines: Vec<Vec<&str>> = stdout.lines().map(|l| l.split('\t').collect()).collect();
+    assert_eq!(lines.len(), 3);
+    for (number, fields) in lines.iter().enumerate() {
+        assert_eq!(fields[0], (number + 1).to_string());
+        assert_eq!(fields[1].len(), 81);
+        assert_eq!(fields[2], "1");
+    }
+    let summary = String::from_utf8(solved.stderr).unwrap();
+    assert!(summary.contains("3 unique, 0 multiple"), "{}", summary);
+
+    let failed = sudoku(&["bulk", "--quiet"], &format!("{}\n", ".".repeat(81)));
+    assert_eq!(failed.status.code(), Some(1));
+    assert!(failed.stdout.is_empty());
+}
+
+#[test]
+fn drawings_have_no_color_when_piped() {
+    let puzzle = format!("{}\n", ".".repeat(81));
+    let plain = sudoku(&["convert", "--format", "unicode"], &puzzle);
+    assert!(plain.status.success());
+    let text = String::from_utf8(plain.stdout).unwrap();
+    assert_eq!(text.lines().count(), 13);
+    assert!(!text.contains('\x1b'));
+
+    let colored = sudoku(&["convert", "-f", "ascii", "--color", "always"], &puzzle);
+    assert!(String::from_utf8(colored.stdout).unwrap().contains('\x1b'));
+}
+
+#[test]
+fn svg_output_is_one_document_per_puzzle() {
+    let puzzles = format!("{0}\n{0}\n", ".".repeat(81));
+    let svg = sudoku(
+        &["convert", "--format", "svg", "--cell-size", "30"],
+        &puzzles,
+    );
+    assert!(svg.status.success());
+    let text = String::from_utf8(svg.stdout).unwrap();
+    assert_eq!(text.matches("<svg ").count(), 2);
+    assert!(text.contains("width=\"273.75\""));
+}
+
+#[test]
+fn booklet_writes_a_pdf() {
+    assert_eq!(sudoku(&["booklet"], "").status.code(), Some(2));
+    let booklet = sudoku(
+        &["booklet", "-o", "-", "-n", "2", "-d", "easy", "--seed", "5"],
+        "",
+    );
+    assert!(booklet.status.success());
+    assert!(booklet.stdout.starts_with(b"%PDF-1.4\n"));
+    assert!(booklet.stdout.ends_with(b"%%EOF\n"));
+}
+
+#[test]
+fn variants_change_the_rules_of_every_command() {
+    let generated = sudoku(
+        &[
+            "generate",
+            "--variant",
+            "diagonal",
+            "--minimal",
+            "--seed",
+            "6",
+        ],
+        "",
+    );
+    assert!(generated.status.success());
+    let puzzle = String::from_utf8(generated.stdout).unwrap();
+
+    let counted = sudoku(&["count", "--variant", "diagonal"], &puzzle);
+    assert_eq!(String::from_utf8_lossy(&counted.stdout), "1\n");
+    let classic = sudoku(&["count", "--limit", "2"], &puzzle);
+    for command in ["count", "bulk"] {
+        let limited = sudoku(&[command, "--limit", "1"], &puzzle);
+        assert_eq!(limited.status.code(), Some(2));
+    }
+    assert_eq!(String::from_utf8_lossy(&classic.stdout), "2+\n");
+
+    let svg = sudoku(&["solve", "--variant", "diagonal", "-f", "svg"], &puzzle);
+    assert_eq!(
+        String::from_utf8(svg.stdout)
+            .unwrap()
+            .matches("<rect ")
+            .count(),
+        18
+    );
+    let broken = format!("5{}5\n", ".".repeat(79));
+    let valid = sudoku(&["validate", "--variant", "diagonal"], &broken);
+    assert_eq!(valid.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&valid.stdout).contains("diagonal 1"));
+    assert!(!String::from_utf8_lossy(&sudoku(&["validate"], &broken).stdout).contains("diagonal"));
+
+    assert_eq!(
+        sudoku(&["count", "--variant", "killer"], &puzzle)
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn killer_puzzles_list_their_cages() {
+    let generated = sudoku(
+        &[
+            "generate",
+            "--variant",
+            "killer",
+            "-b",
+            "2x3",
+            "--seed",
+            "3",
+        ],
+        "",
+    );
+    assert!(generated.status.success());
+    let stdout = String::from_utf8(generated.stdout).unwrap();
+    let mut lines = stdout.lines();
+    assert_eq!(lines.next(), Some(".".repeat(36).as_str()));
+    let cells: usize = lines
+        .map(|line| line.split_whitespace().skip(1).count())
+        .sum();
+    assert_eq!(cells, 36);
+
+    let svg = sudoku(
+        &["generate", "--variant", "killer", "-b", "2x3", "-f", "svg"],
+        "",
+    );
+    assert!(String::from_utf8(svg.stdout)
+        .unwrap()
+        .contains("stroke-dasharray"));
+    assert_eq!(
+        sudoku(&["generate", "--variant", "killer", "--minimal"], "")
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn killer_puzzles_are_solved_from_their_cages() {
+    let generated = sudoku(&["generate", "--variant", "killer", "-b", "2x3"], "");
+    let stdout = String::from_utf8(generated.stdout).unwrap();
+    let (givens, cages) = stdout.split_once('\n').unwrap();
+    let one_line = cages.trim_end().replace('\n', "; ");
+    let killer = ["--variant", "killer", "--cages"];
+
+    let solved = sudoku(&[&["solve"], &killer[..], &[cages, givens]].concat(), "");
+    assert!(solved.status.success());
+    let solution = String::from_utf8(solved.stdout).unwrap();
+    assert_eq!(solution.trim_end().len(), 36);
+    assert!(!solution.contains('.'));
+    let counted = sudoku(
+        &[&["count"], &killer[..], &[&one_line, givens]].concat(),
+        "",
+    );
+    assert_eq!(String::from_utf8_lossy(&counted.stdout), "1\n");
+    let validated = sudoku(&[&["validate"], &killer[..], &[cages, givens]].concat(), "");
+    assert_eq!(String::from_utf8_lossy(&validated.stdout), "valid\n");
+    let checked = sudoku(
+        &[&["validate"], &killer[..], &[cages, solution.trim_end()]].concat(),
+        "",
+    );
+    assert_eq!(String::from_utf8_lossy(&checked.stdout), "valid\n");
+
+    for args in [
+        &["count", "--variant", "killer", givens][..],
+        &["count", "--cages", cages, givens],
+        &[
+            "count",
+            "--variant",
+            "killer",
+            "--cages",
+            "3: r1c1 x",
+            givens,
+        ],
+    ] {
+        assert_eq!(sudoku(args, "").status.code(), Some(2), "{:?}", args);
+    }
+}
+
+#[test]
+fn jigsaw_puzzles_print_their_regions() {
+    let generated = sudoku(&["generate", "--variant", "jigsaw", "--seed", "5"], "");
+    assert!(generated.status.success());
+    let stdout = String::from_utf8(generated.stdout).unwrap();
+    let (puzzle, map) = stdout.split_once('\n').unwrap();
+    assert_eq!(puzzle.len(), 81);
+    assert_eq!(map.lines().count(), 9);
+    let regions: String = map.split_whitespace().collect();
+
+    let counted = sudoku(
+        &["count", "--variant", "jigsaw", "--regions", &regions],
+        puzzle,
+    );
+    assert_eq!(String::from_utf8_lossy(&counted.stdout), "1\n");
+    assert_eq!(
+        sudoku(&["count", "--regions", &regions], puzzle)
+            .status
+            .code(),
+        Some(2)
+    );
+    assert_eq!(
+        sudoku(&["count", "--variant", "jigsaw"], puzzle)
+            .status
+            .code(),
+        Some(2)
+    );
+}
+
+#[test]
+fn regions_and_cages_must_fit_the_grid() {
+    let puzzle = ".".repeat(81);
+    for command in ["solve", "count", "validate", "convert"] {
+        let mismatched = sudoku(
+            &[
+                command,
+                "--box",
+                "3x3",
+                "--variant",
+                "jigsaw",
+                "--regions",
+                "1112 3122 3342 3444",
+                &puzzle,
+            ],
+            "",
+        );
+        assert_eq!(mismatched.status.code(), Some(2), "{}", command);
+        assert!(String::from_utf8_lossy(&mismatched.stderr)
+            .contains("--regions has 16 cells, but the grid has 81"));
+    }
+    let cages = sudoku(
+        &[
+            "count",
+            "--box",
+            "2x2",
+            "--variant",
+            "killer",
+            "--cages",
+            "3: r1c1 r9c9",
+            &puzzle,
+        ],
+        "",
+    );
+    assert_eq!(cages.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&cages.stderr)
+        .contains("--cages are for a grid of 81 cells, but the grid has 16"));
+}
+
+#[test]
+fn only_classic_puzzles_are_rated() {
+    for variant in ["diagonal", "jigsaw"] {
+        for flag in ["--difficulty", "--technique", "--max-technique"] {
+            let value = if flag == "--difficulty" {
+                "hard"
+            } else {
+                "x-wing"
+            };
+            let generated = sudoku(&["generate", "--variant", variant, flag, value], "");
+            assert_eq!(generated.status.code(), Some(2), "{} {}", variant, flag);
+            assert!(String::from_utf8_lossy(&generated.stderr).contains("classic puzzles"));
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use sudoku::{
+    Columns, Constraint, Generator, Grid, PuzzleOptions, Rows, Rules, Shape, Solver, CELLS, SIZE,
+};
+
+/** The centre cells of the nine boxes hold different digits. */
+#[derive(Debug)]
+struct CentreDots;
+
+impl Constraint for CentreDots {
+    fn name(&self) -> &str {
+        "centre dots"
+    }
+
+    fn regions(&self, _shape: Shape) -> Vec<Vec<usize>> {
+        vec![(0..SIZE)
+            .map(|b| (b / 3 * 3 + 1) * SIZE + b % 3 * 3 + 1)
+            .collect()]
+    }
+}
+
+/** The cells of the main diagonal hold odd digits. */
+#[derive(Debug)]
+struct OddDiagonal;
+
+impl Constraint for OddDiagonal {
+    fn name(&self) -> &str {
+        "odd diagonal"
+    }
+
+    fn eliminates(&self) -> bool {
+        true
+    }
+
+    fn eliminate(&self, _values: &[u8], cell: usize, candidates: u32) -> u32 {
+        if cell.is_multiple_of(SIZE + 1) {
+            candidates & 0b1_0101_0101
+        } else {
+            candidates
+        }
+    }
+
+    fn is_satisfied(&self, grid: &Grid) -> bool {
+        (0..SIZE).all(|i| grid.get(i, i).is_none_or(|digit| digit % 2 == 1))
+    }
+}
+
+#[test]
+fn classic_rules_match_the_default_solver() {
+    let grid: Grid =
+        "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......"
+            .parse()
+            .unwrap();
+    let classic = Solver::with_rules(Rules::classic());
+    assert!(classic.rules().is_classic());
+    assert_eq!(classic.solve(&grid), Solver::new().solve(&grid));
+    assert_eq!(
+        format!("{:?}", Rules::default()),
+        "[\"rows\", \"columns\", \"boxes\"]"
+    );
+}
+
+#[test]
+fn rules_count_the_grids_they_allow() {
+    let shape = Shape::new(2, 2).unwrap();
+    let latin = Rules::empty().with(Rows).with(Columns);
+    assert!(!latin.is_classic());
+    assert_eq!(Solver::with_rules(latin).count(&Grid::new(shape)), 576);
+    assert_eq!(Solver::new().count(&Grid::new(shape)), 288);
+}
+
+#[test]
+fn added_constraints_shape_the_generated_puzzles() {
+    let rules = Rules::classic().with(CentreDots).with(OddDiagonal);
+    let options = PuzzleOptions::new(CELLS).minimal(true).rules(rules.clone());
+    let puzzle = Generator::from_seed(22).generate(&options).unwrap();
+    let solution = puzzle.solution();
+    assert!(rules.is_satisfied(solution));
+    assert!(Rules::classic().is_satisfied(solution));
+    let mut centres: Vec<u8> = (0..SIZE)
+        .map(|b| solution.get(b / 3 * 3 + 1, b % 3 * 3 + 1).unwrap())
+        .collect();
+    centres.sort();
+    assert_eq!(centres, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+    let solver = Solver::with_rules(rules);
+    assert!(solver.has_unique_solution(puzzle.givens()));
+    assert_eq!(solver.solve(puzzle.givens()).as_ref(), Some(solution));
+    assert!(solver.is_minimal(puzzle.givens()));
+}
+
+#[test]
+fn is_safe_consults_every_constraint() {
+    let rules = Rules::classic().with(CentreDots).with(OddDiagonal);
+    let mut grid = Grid::empty();
+    grid.set(1, 1, 5).unwrap();
+    assert!(!rules.is_safe(&grid, 4, 4, 5));
+    assert!(grid.is_safe(4, 4, 5));
+    assert!(!rules.is_safe(&grid, 0, 0, 2));
+    assert!(rules.is_safe(&grid, 0, 0, 3));
+    assert!(!rules.is_safe(&grid, 0, 0, 0));
+    assert!(!rules.is_safe(&grid, 0, 0, 10));
+    assert!(!Rules::classic().is_safe(&Grid::new(Shape::new(2, 2).unwrap()), 0, 0, 5));
+    grid.set(4, 4, 5).unwrap();
+    assert!(!rules.is_satisfied(&grid));
+    assert!(Rules::classic().is_satisfied(&grid));
+}
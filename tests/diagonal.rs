@@ -0,0 +1,106 @@
+use sudoku::{
+    Diagonals, Generator, Grid, PuzzleOptions, Renderer, Rules, Shape, Solver, Style, SvgRenderer,
+    Symmetry, CELLS, SIZE,
+};
+
+/** Returns the sorted digits of a diagonal, main when `anti` is false. */
+fn diagonal(grid: &Grid, anti: bool) -> Vec<u8> {
+    let mut digits: Vec<u8> = (0..grid.size())
+        .map(|i| {
+            let col = if anti { grid.size() - 1 - i } else { i };
+            grid.get(i, col).unwrap()
+        })
+        .collect();
+    digits.sort();
+    digits
+}
+
+#[test]
+fn diagonal_puzzles_are_unique_only_with_the_diagonals() {
+    let rules = Rules::diagonal();
+    assert_eq!(
+        format!("{:?}", rules),
+        "[\"rows\", \"columns\", \"boxes\", \"diagonals\"]"
+    );
+    let options = PuzzleOptions::new(CELLS)
+        .minimal(true)
+        .symmetry(Symmetry::Rotational180)
+        .rules(rules.clone());
+    let puzzle = Generator::from_seed(23).generate(&options).unwrap();
+    let solution = puzzle.solution();
+    for anti in [false, true] {
+        assert_eq!(diagonal(solution, anti), [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    let solver = Solver::with_rules(rules);
+    assert!(solver.has_unique_solution(puzzle.givens()));
+    assert!(solver.is_minimal(puzzle.givens()));
+    assert!(Solver::new().count_solutions(puzzle.givens(), 2) > 1);
+}
+
+#[test]
+fn every_shape_fills_its_diagonals() {
+    let rules = Rules::empty().with(Diagonals);
+    assert!(!rules.is_classic());
+    for (rows, cols) in [(2, 2), (2, 3), (3, 4)] {
+        let shape = Shape::new(rows, cols).unwrap();
+        let solution = Generator::from_seed(24)
+            .solution_with_rules(shape, &Rules::diagonal())
+            .unwrap();
+        assert!(rules.is_satisfied(&solution), "{}", shape);
+        let digits: Vec<u8> = (1..=shape.size() as u8).collect();
+        assert_eq!(diagonal(&solution, true), digits);
+    }
+}
+
+#[test]
+fn renderers_shade_the_diagonals() {
+    let grid = Generator::from_seed(25).solution();
+    let plain = SvgRenderer::new().render(&grid);
+    let shaded = SvgRenderer::new()
+        .diagonals(true)
+        .shade_color("#ddeeff")
+        .render(&grid);
+    assert_eq!(plain.matches("<rect ").count(), 1);
+    assert_eq!(shaded.matches("fill=\"#ddeeff\"").count(), 2 * SIZE - 1);
+
+    let renderer = Renderer::new().diagonals(true);
+    assert_eq!(renderer.render(&grid), Renderer::new().render(&grid));
+    let colored = renderer.color(true).render(&grid);
+    assert_eq!(colored.matches("\x1b[100m").count(), 2 * SIZE - 1);
+}
+
+#[test]
+fn drawings_mark_the_diagonals_without_color() {
+    let shape = Shape::new(2, 2).unwrap();
+    let mut grid = Grid::new(shape);
+    grid.set(0, 1, 1).unwrap();
+    let renderer = Renderer::new().style(Style::Ascii).diagonals(true);
+    assert_eq!(
+        renderer.render(&grid),
+        "+-----+-----+\n\
+         | * 1 | . * |\n\
+         | . * | * . |\n\
+         +-----+-----+\n\
+         | . * | * . |\n\
+         | * . | . * |\n\
+         +-----+-----+\n"
+    );
+    assert!(!renderer.clone().color(true).render(&grid).contains('*'));
+
+    // The 1s share the main diagonal but no row, column or box.
+    grid.clear(0, 1);
+    grid.set(0, 0, 1).unwrap();
+    grid.set(3, 3, 1).unwrap();
+    let repeated = "\x1b[1;31m1";
+    let colored = Renderer::new().diagonals(true).color(true).render(&grid);
+    assert_eq!(colored.matches(repeated).count(), 2);
+    assert_eq!(
+        Renderer::new()
+            .color(true)
+            .render(&grid)
+            .matches(repeated)
+            .count(),
+        0
+    );
+}
@@ -0,0 +1,113 @@
+/*!
+ * Statistical checks that the search filling solution grids is not biased
+ * towards a small family of grids.
+ *
+ * Classic grids end with a random relabeling and shuffle of rows, columns
+ * and bands, which spreads digits evenly whatever order the search took.
+ * The samples here are filled under jigsaw rules whose regions are the
+ * boxes: the same constraints, but no transform to hide the search order.
+ */
+
+use std::collections::HashSet;
+
+use sudoku::{Generator, Grid, Regions, Rules, Shape, SIZE};
+
+const SAMPLES: usize = 900;
+
+fn samples() -> Vec<Grid> {
+    let mut generator = Generator::from_seed(0x5eed);
+    let rules = Rules::jigsaw(Regions::boxes(Shape::CLASSIC));
+    (0..SAMPLES)
+        .map(|_| {
+            generator
+                .solution_with_rules(Shape::CLASSIC, &rules)
+                .unwrap()
+        })
+        .collect()
+}
+
+/** Pearson's chi-squared statistic of digit counts against a uniform spread. */
+fn chi_squared(counts: &[usize; SIZE]) -> f64 {
+    let expected = SAMPLES as f64 / SIZE as f64;
+    counts
+        .iter()
+        .map(|&count| (count as f64 - expected).powi(2) / expected)
+        .sum()
+}
+
+fn digit_set(values: impl Iterator<Item = Option<u8>>) -> u16 {
+    values.fold(0, |set, value| set | 1 << value.unwrap())
+}
+
+#[test]
+fn first_row_and_box_digits_are_uniform() {
+    let grids = samples();
+    let cells = (0..SIZE)
+        .map(|col| (0, col))
+        .chain((1..3).flat_map(|row| (0..3).map(move |col| (row, col))));
+    for (row, col) in cells {
+        let mut counts = [0; SIZE];
+        for grid in &grids {
+            counts[grid.get(row, col).unwrap() as usize - 1] += 1;
+        }
+        // 8 degrees of freedom; 40 has a p-value below 1e-5.
+        let statistic = chi_squared(&counts);
+        assert!(
+            statistic < 40.0,
+            "digits in ({}, {}) are skewed: {:?} (chi^2 = {:.1})",
+            row,
+            col,
+            counts,
+            statistic
+        );
+    }
+}
+
+#[test]
+fn box_patterns_do_not_favour_rows_over_columns() {
+    // The second row of the first box reuses the first row of the second box
+    // exactly as often as the transposed pattern does in a uniform sample.
+    let grids = samples();
+    let mut by_row = 0;
+    let mut by_col = 0;
+    for grid in &grids {
+        let row_1 = digit_set((0..3).map(|col| grid.get(1, col)));
+        let row_0 = digit_set((3..6).map(|col| grid.get(0, col)));
+        let col_1 = digit_set((0..3).map(|row| grid.get(row, 1)));
+        let col_0 = digit_set((3..6).map(|row| grid.get(row, 0)));
+        by_row += usize::from(row_1 == row_0);
+        by_col += usize::from(col_1 == col_0);
+    }
+    let p = (by_row + by_col) as f64 / (2 * SAMPLES) as f64;
+    let sigma = (2.0 * p * (1.0 - p) / SAMPLES as f64).sqrt().max(1e-9);
+    let z = (by_row as f64 - by_col as f64).abs() / SAMPLES as f64 / sigma;
+    assert!(
+        z < 5.0,
+        "row pattern seen {} times, column pattern {} times",
+        by_row,
+        by_col
+    );
+}
+
+#[test]
+fn top_bands_are_not_relabelings_of_each_other() {
+    // Relabel each grid so its first row reads 1..=9; an unbiased generator
+    // then still produces many different second rows.
+    let grids = samples();
+    let mut patterns = HashSet::new();
+    for grid in &grids {
+        let mut labels = [0u8; SIZE + 1];
+        for col in 0..SIZE {
+            labels[grid.get(0, col).unwrap() as usize] = col as u8 + 1;
+        }
+        let second_row: Vec<u8> = (0..SIZE)
+            .map(|col| labels[grid.get(1, col).unwrap() as usize])
+            .collect();
+        patterns.insert(second_row);
+    }
+    assert!(
+        patterns.len() > SAMPLES / 4,
+        "only {} distinct normalized second rows",
+        patterns.len()
+    );
+}
@@ -0,0 +1,75 @@
+use sudoku::{parse, parse_many, Format, Generator, ParseError, ParseErrorKind, PuzzleOptions};
+
+#[test]
+fn every_format_round_trips() {
+    let mut generator = Generator::from_seed(14);
+    let puzzle = generator.generate(&PuzzleOptions::new(30)).unwrap();
+    for format in Format::ALL {
+        let text = format.write(puzzle.givens());
+        assert_eq!(parse(&text).as_ref(), Ok(puzzle.givens()), "{}", format);
+        assert_eq!(format.name().parse(), Ok(format));
+    }
+}
+
+#[test]
+fn errors_point_at_the_offending_character() {
+    let ss = "\
+#A some author
+53.|.7.|...
+6..|195|...
+.98|...|.6.
+---+---+---
+8..|.6.|..3
+4..|8x3|..1
+";
+    assert_eq!(
+        parse(ss),
+        Err(ParseError {
+            kind: ParseErrorKind::InvalidCharacter('x'),
+            line: 7,
+            column: 6,
+        })
+    );
+    let short = ss.replace('x', "0");
+    assert_eq!(
+        parse(&short).unwrap_err().kind,
+        ParseErrorKind::TooFewCells(45)
+    );
+    let long = format!("{}{}", ".".repeat(81), "5");
+    assert_eq!(
+        parse(&long),
+        Err(ParseError {
+            kind: ParseErrorKind::TooManyCells,
+            line: 1,
+            column: 82,
+        })
+    );
+}
+
+#[test]
+fn collections_mix_formats() {
+    let mut generator = Generator::from_seed(15);
+    let grids: Vec<_> = (0..3)
+        .map(|_| {
+            generator
+                .generate(&PuzzleOptions::new(28))
+                .unwrap()
+                .givens()
+                .clone()
+        })
+        .collect();
+    let text = format!(
+        "# puzzles\n{}{}\n{}\n12345\n",
+        Format::Line.write(&grids[0]),
+        Format::Sdk.write(&grids[1]),
+        Format::SimpleSudoku.write(&grids[2]),
+    );
+    let parsed = parse_many(&text);
+    assert_eq!(parsed.len(), 4);
+    for (grid, result) in grids.iter().zip(&parsed) {
+        assert_eq!(result.as_ref(), Ok(grid));
+    }
+    let error = parsed[3].as_ref().unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::TooFewCells(5));
+    assert_eq!((error.line, error.column), (25, 6));
+}
@@ -0,0 +1,156 @@
+use sudoku::{
+    GenerationError, Generator, PuzzleOptions, Regions, Rules, Shape, Symmetry, Technique, Tier,
+    CELLS, SIZE,
+};
+
+#[test]
+fn equal_seeds_give_equal_puzzles() {
+    let options = PuzzleOptions::new(40);
+    let first = Generator::from_seed(42).generate(&options).unwrap();
+    let second = Generator::from_seed(42).generate(&options).unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn different_seeds_give_different_solutions() {
+    let first = Generator::from_seed(1).solution();
+    let second = Generator::from_seed(2).solution();
+    assert_ne!(first, second);
+}
+
+#[test]
+fn clue_target_is_met_exactly() {
+    let puzzle = Generator::from_seed(3)
+        .generate(&PuzzleOptions::new(30))
+        .unwrap();
+    assert_eq!(puzzle.clues(), 30);
+    assert!(sudoku::has_unique_solution(puzzle.givens()));
+}
+
+#[test]
+fn unreachable_target_reports_fewest_clues() {
+    let options = PuzzleOptions::new(16).max_attempts(2);
+    match Generator::from_seed(4).generate(&options) {
+        Err(GenerationError::Exhausted {
+            target, reached, ..
+        }) => {
+            assert_eq!(target, 16);
+            assert!(reached > 16);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn symmetric_puzzles_keep_their_clue_pattern() {
+    for symmetry in [
+        Symmetry::Rotational180,
+        Symmetry::Rotational90,
+        Symmetry::MirrorHorizontal,
+        Symmetry::MirrorVertical,
+        Symmetry::Diagonal,
+        Symmetry::Dihedral,
+    ] {
+        let options = PuzzleOptions::new(32).symmetry(symmetry);
+        let puzzle = Generator::from_seed(5).generate(&options).unwrap();
+        let givens = puzzle.givens();
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                let filled = givens.get(row, col).is_some();
+                for (r, c) in symmetry.orbit(row, col) {
+                    assert_eq!(givens.get(r, c).is_some(), filled, "{:?}", symmetry);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn minimal_puzzles_have_no_redundant_clues() {
+    let mut generator = Generator::from_seed(6);
+    let puzzle = generator
+        .generate(&PuzzleOptions::new(30).minimal(true))
+        .unwrap();
+    assert!(puzzle.clues() <= 30);
+    assert!(sudoku::is_minimal(puzzle.givens()));
+
+    let full = generator.solution();
+    let minimized = sudoku::minimize(&full);
+    assert!(sudoku::is_minimal(&minimized));
+    assert!(!sudoku::is_minimal(&full));
+}
+
+#[test]
+fn puzzles_land_in_the_requested_tier() {
+    let mut generator = Generator::from_seed(11);
+    for tier in [Tier::Easy, Tier::Medium, Tier::Hard] {
+        let options = PuzzleOptions::new(CELLS).difficulty(tier);
+        let puzzle = generator.generate(&options).unwrap();
+        assert_eq!(sudoku::rate(puzzle.givens()).unwrap().tier, tier);
+    }
+}
+
+#[test]
+fn missed_tier_reports_what_was_found() {
+    let options = PuzzleOptions::new(CELLS)
+        .difficulty(Tier::Diabolical)
+        .max_attempts(1);
+    match Generator::from_seed(12).generate(&options) {
+        Err(GenerationError::DifficultyNotReached { wanted, seen, .. }) => {
+            assert_eq!(wanted, Tier::Diabolical);
+            assert_eq!(seen.values().sum::<usize>(), 1);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+}
+
+#[test]
+fn required_technique_is_the_bottleneck() {
+    let mut generator = Generator::from_seed(13);
+    let options = PuzzleOptions::new(CELLS).technique(Technique::HiddenPair);
+    let puzzle = generator.generate(&options).unwrap();
+    let rating = sudoku::rate(puzzle.givens()).unwrap();
+    assert!(rating.solved);
+    assert_eq!(rating.hardest, Some(Technique::HiddenPair));
+
+    let options = PuzzleOptions::new(CELLS)
+        .technique(Technique::PointingPair)
+        .max_technique(Technique::NakedPair);
+    let puzzle = generator.generate(&options).unwrap();
+    let rating = sudoku::rate(puzzle.givens()).unwrap();
+    assert!(rating.counts.contains_key(&Technique::PointingPair));
+    assert!(rating.hardest <= Some(Technique::NakedPair));
+}
+
+#[test]
+fn maximum_technique_must_not_be_easier_than_the_required_one() {
+    let options = PuzzleOptions::new(CELLS)
+        .technique(Technique::XWing)
+        .max_technique(Technique::NakedPair);
+    assert_eq!(
+        Generator::from_seed(14).generate(&options),
+        Err(GenerationError::InvalidTechniques {
+            required: Technique::XWing,
+            max: Technique::NakedPair,
+        })
+    );
+}
+
+#[test]
+fn ratings_need_the_classic_rules() {
+    let jigsaw = Rules::jigsaw(Regions::boxes(Shape::CLASSIC));
+    for rules in [Rules::diagonal(), jigsaw] {
+        let base = PuzzleOptions::new(CELLS).rules(rules);
+        for options in [
+            base.clone().difficulty(Tier::Hard),
+            base.clone().technique(Technique::NakedPair),
+            base.clone().max_technique(Technique::XWing),
+        ] {
+            assert_eq!(
+                Generator::from_seed(15).generate(&options),
+                Err(GenerationError::UnratedRules)
+            );
+        }
+        assert!(Generator::from_seed(15).generate(&base).is_ok());
+    }
+}
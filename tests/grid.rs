@@ -0,0 +1,101 @@
+use sudoku::{Grid, GridError, Shape, CELLS};
+
+#[test]
+fn values_must_fill_the_grid() {
+    assert_eq!(
+        Grid::from_values(&[0; CELLS - 1]),
+        Err(GridError::WrongLength {
+            expected: CELLS,
+            found: CELLS - 1
+        })
+    );
+    assert_eq!(
+        Grid::with_values(Shape::CLASSIC, &[0; 16]),
+        Err(GridError::WrongLength {
+            expected: CELLS,
+            found: 16
+        })
+    );
+    assert_eq!(Grid::from_values(&[0; CELLS]), Ok(Grid::empty()));
+}
+
+#[test]
+fn values_must_be_digits_of_the_grid() {
+    let mut values = [0; CELLS];
+    values[10] = 10;
+    assert_eq!(
+        Grid::from_values(&values),
+        Err(GridError::OutOfRange {
+            row: 1,
+            col: 1,
+            value: 10
+        })
+    );
+    let mut rows = [[0; 9]; 9];
+    rows[8][0] = 12;
+    assert_eq!(
+        Grid::from_rows(&rows),
+        Err(GridError::OutOfRange {
+            row: 8,
+            col: 0,
+            value: 12
+        })
+    );
+
+    let mut grid = Grid::empty();
+    grid.set(4, 4, 7).unwrap();
+    for value in [0, 10] {
+        assert_eq!(
+            grid.set(4, 4, value),
+            Err(GridError::OutOfRange {
+                row: 4,
+                col: 4,
+                value
+            })
+        );
+    }
+    assert_eq!(grid.get(4, 4), Some(7));
+}
+
+#[test]
+fn set_and_clear_round_trip() {
+    let mut grid = Grid::empty();
+    assert_eq!(grid.find_empty(), Some((0, 0)));
+    for cell in 0..CELLS {
+        let (row, col) = (cell / 9, cell % 9);
+        grid.set(row, col, (cell % 9) as u8 + 1).unwrap();
+        assert_eq!(grid.get(row, col), Some((cell % 9) as u8 + 1));
+    }
+    assert!(grid.is_complete());
+    assert_eq!(grid.filled_count(), CELLS);
+    assert_eq!(Grid::from_values(&grid.to_values()).as_ref(), Ok(&grid));
+
+    grid.clear(2, 5);
+    assert_eq!(grid.get(2, 5), None);
+    assert_eq!(grid.find_empty(), Some((2, 5)));
+    assert_eq!(grid.to_values()[2 * 9 + 5], 0);
+    assert_eq!(grid.filled_count(), CELLS - 1);
+    grid.set(2, 5, 6).unwrap();
+    assert!(grid.is_complete());
+    for row in 0..9 {
+        for col in 0..9 {
+            grid.clear(row, col);
+        }
+    }
+    assert_eq!(grid, Grid::empty());
+}
+
+#[test]
+fn only_digits_of_the_grid_are_safe() {
+    let mut grid = Grid::empty();
+    grid.set(0, 0, 5).unwrap();
+    assert!(grid.is_safe(4, 4, 5));
+    assert!(!grid.is_safe(0, 8, 5));
+    assert!(!grid.is_safe(8, 0, 5));
+    assert!(!grid.is_safe(2, 2, 5));
+    assert!(!grid.is_safe(4, 4, 0));
+    assert!(!grid.is_safe(4, 4, 10));
+    let small = Grid::new(Shape::new(2, 2).unwrap());
+    assert!(small.is_safe(3, 3, 4));
+    assert!(!small.is_safe(3, 3, 5));
+}
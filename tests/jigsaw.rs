@@ -0,0 +1,140 @@
+use sudoku::{
+    Generator, Grid, PuzzleOptions, RegionError, Regions, Renderer, Rules, Shape, Solver, Style,
+    SvgRenderer,
+};
+
+/** A 4x4 layout with no region along a box. */
+const LAYOUT: &str = "1112 3122 3342 3444";
+
+#[test]
+fn region_maps_must_make_connected_regions() {
+    let shape = Shape::new(2, 2).unwrap();
+    assert_eq!(
+        Regions::new(shape, &[0; 15]),
+        Err(RegionError::WrongLength {
+            expected: 16,
+            found: 15
+        })
+    );
+    let mut map = [0, 0, 0, 1, 2, 0, 1, 1, 2, 2, 3, 1, 2, 3, 3, 3];
+    assert!(Regions::new(shape, &map).is_ok());
+    map[15] = 4;
+    assert_eq!(
+        Regions::new(shape, &map),
+        Err(RegionError::RegionOutOfRange {
+            cell: 15,
+            region: 4
+        })
+    );
+    map[15] = 2;
+    assert_eq!(
+        Regions::new(shape, &map),
+        Err(RegionError::WrongSize {
+            region: 2,
+            cells: 5
+        })
+    );
+    assert_eq!(
+        "1212 1212 3434 3434".parse::<Regions>(),
+        Err(RegionError::Disconnected(0))
+    );
+    assert_eq!(
+        "1112 3122 3342 344#".parse::<Regions>(),
+        Err(RegionError::InvalidSymbol('#'))
+    );
+
+    let regions: Regions = LAYOUT.parse().unwrap();
+    assert_eq!(regions.to_string(), "1112\n3122\n3342\n3444\n");
+    assert_eq!(regions.to_string().parse(), Ok(regions.clone()));
+    assert_eq!(regions.region_of(1, 1), 0);
+    assert_eq!(regions.cells_of(3), [10, 13, 14, 15]);
+    assert_eq!(
+        Regions::boxes(shape).to_string(),
+        "1122\n1122\n3344\n3344\n"
+    );
+}
+
+#[test]
+fn jigsaw_rules_replace_the_boxes() {
+    let shape = Shape::new(2, 2).unwrap();
+    let boxes = Solver::with_rules(Rules::jigsaw(Regions::boxes(shape)));
+    assert_eq!(boxes.count(&Grid::new(shape)), 288);
+
+    let regions: Regions = LAYOUT.parse().unwrap();
+    let rules = Rules::jigsaw(regions);
+    assert_eq!(
+        format!("{:?}", rules),
+        "[\"rows\", \"columns\", \"regions\"]"
+    );
+    let grid: Grid = "1...............".parse().unwrap();
+    let solution = Solver::with_rules(rules.clone()).solve(&grid).unwrap();
+    assert!(rules.is_satisfied(&solution));
+    assert!(!Rules::classic().is_satisfied(&solution));
+}
+
+#[test]
+fn generated_layouts_make_unique_puzzles() {
+    let mut generator = Generator::from_seed(25);
+    let regions = generator.regions(Shape::CLASSIC);
+    assert_ne!(regions, Regions::boxes(Shape::CLASSIC));
+    let rules = Rules::jigsaw(regions.clone());
+    let options = PuzzleOptions::new(81).minimal(true).rules(rules.clone());
+    let puzzle = generator.generate(&options).unwrap();
+    let solution = puzzle.solution();
+    for region in 0..9 {
+        let mut digits: Vec<u8> = regions
+            .cells_of(region)
+            .iter()
+            .map(|&cell| solution.get(cell / 9, cell % 9).unwrap())
+            .collect();
+        digits.sort();
+        assert_eq!(digits, [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    let solver = Solver::with_rules(rules);
+    assert!(solver.has_unique_solution(puzzle.givens()));
+    assert!(solver.is_minimal(puzzle.givens()));
+}
+
+#[test]
+fn renderers_draw_the_region_borders() {
+    let regions: Regions = LAYOUT.parse().unwrap();
+    let grid: Grid = "1...............".parse().unwrap();
+    let text = Renderer::new()
+        .style(Style::Ascii)
+        .regions(&regions)
+        .render(&grid);
+    assert_eq!(
+        text,
+        "+-----------+---+\n\
+         | 1   .   . | . |\n\
+         +---+   +---+   |\n\
+         | . | . | .   . |\n\
+         |   +---+---+   |\n\
+         | .   . | . | . |\n\
+         |   +---+   +---+\n\
+         | . | .   .   . |\n\
+         +---+-----------+\n"
+    );
+
+    let svg = SvgRenderer::new()
+        .regions(&regions)
+        .pencil_marks(true)
+        .render(&grid);
+    assert_eq!(svg.matches("<text ").count(), 1);
+    assert_eq!(svg.matches("<path ").count(), 2);
+}
+
+#[test]
+#[should_panic(expected = "another grid size")]
+fn drawings_refuse_regions_of_another_size() {
+    let regions: Regions = LAYOUT.parse().unwrap();
+    Renderer::new().regions(&regions).render(&Grid::empty());
+}
+
+#[test]
+#[should_panic(expected = "another grid size")]
+fn svg_refuses_regions_of_another_size() {
+    let regions: Regions = LAYOUT.parse().unwrap();
+    SvgRenderer::new().regions(&regions).render(&Grid::empty());
+}
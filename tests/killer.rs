@@ -0,0 +1,180 @@
+use sudoku::{
+    Cage, CageError, Cages, Constraint, Generator, Grid, KillerOptions, KillerPuzzle, Regions,
+    Rules, Shape, Solver, SvgRenderer,
+};
+
+/** Checks that every cage holds distinct digits adding up to its sum. */
+fn assert_cages_hold(puzzle: &KillerPuzzle) {
+    let solution = puzzle.solution();
+    let size = solution.size();
+    let mut covered = vec![false; solution.shape().cells()];
+    for cage in puzzle.cages().cages() {
+        let mut digits: Vec<u8> = cage
+            .cells()
+            .iter()
+            .map(|&cell| solution.get(cell / size, cell % size).unwrap())
+            .collect();
+        let sum: u32 = digits.iter().map(|&digit| u32::from(digit)).sum();
+        assert_eq!(sum, cage.sum());
+        digits.sort();
+        digits.dedup();
+        assert_eq!(digits.len(), cage.cells().len());
+        for &cell in cage.cells() {
+            assert!(!covered[cell]);
+            covered[cell] = true;
+        }
+    }
+    assert!(covered.iter().all(|&cell| cell));
+}
+
+#[test]
+fn cages_reject_bad_layouts() {
+    let shape = Shape::CLASSIC;
+    assert_eq!(
+        Cages::new(shape, vec![Cage::new(3, &[0, 81])]),
+        Err(CageError::CellOutOfRange(81))
+    );
+    assert_eq!(
+        Cages::new(shape, vec![Cage::new(3, &[0, 1]), Cage::new(9, &[1, 2])]),
+        Err(CageError::SharedCell(1))
+    );
+    assert_eq!(
+        Cages::new(shape, vec![Cage::new(3, &[0, 1]), Cage::new(18, &[2, 3])]),
+        Err(CageError::ImpossibleSum { cage: 1, sum: 18 })
+    );
+    assert_eq!(Cage::new(4, &[10, 1, 10]).cells(), [1, 10]);
+}
+
+#[test]
+fn cages_read_back_what_they_write() {
+    let puzzle = Generator::from_seed(26)
+        .generate_killer(&KillerOptions::new().shape(Shape::new(2, 3).unwrap()))
+        .unwrap();
+    let text = puzzle.cages().to_string();
+    assert_eq!(text.parse(), Ok(puzzle.cages().clone()));
+    assert_eq!(text.replace('\n', "; ").parse(), Ok(puzzle.cages().clone()));
+
+    let cages: Cages = "3: R1C1 r1c2\n\n24: r2c1 r2c2 r2c3; 9: r9c9"
+        .parse()
+        .unwrap();
+    assert_eq!(cages.shape(), Shape::CLASSIC);
+    assert_eq!(cages.cage_of(1, 2).map(Cage::cells), Some(&[9, 10, 11][..]));
+    assert_eq!(
+        "3 r1c1 r1c2".parse::<Cages>(),
+        Err(CageError::InvalidCage("3 r1c1 r1c2".to_string()))
+    );
+    assert_eq!(
+        "3:".parse::<Cages>(),
+        Err(CageError::InvalidCage("3:".to_string()))
+    );
+    for cell in ["r0c1", "r1c26", "c1r1", "r1"] {
+        assert_eq!(
+            format!("3: r1c1 {}", cell).parse::<Cages>(),
+            Err(CageError::InvalidCell(cell.to_string()))
+        );
+    }
+    assert_eq!(" \n".parse::<Cages>(), Err(CageError::Empty));
+    assert_eq!(
+        "3: r1c1 r1c2\n4: r1c2 r4c4".parse::<Cages>(),
+        Err(CageError::SharedCell(1))
+    );
+}
+
+#[test]
+fn cage_sums_prune_the_search() {
+    let cages = Cages::new(
+        Shape::CLASSIC,
+        vec![Cage::new(3, &[0, 1]), Cage::new(24, &[9, 10, 11])],
+    )
+    .unwrap();
+    assert_eq!(cages.cage_of(0, 1).map(Cage::sum), Some(3));
+    assert_eq!(cages.cage_of(1, 0).map(Cage::sum), Some(24));
+    assert!(cages.cage_of(0, 2).is_none());
+    assert_eq!(cages.to_string(), "3: r1c1 r1c2\n24: r2c1 r2c2 r2c3\n");
+
+    let values = vec![0; 81];
+    assert_eq!(cages.eliminate(&values, 0, 0x1ff), 0b11);
+    assert_eq!(cages.eliminate(&values, 10, 0x1ff), 0b1_1100_0000);
+    assert_eq!(cages.eliminate(&[0; 16], 0, 0xf), 0xf);
+    let rules = Rules::classic().with(cages);
+    let mut grid = Grid::empty();
+    assert!(!rules.is_safe(&grid, 0, 0, 3));
+    assert!(rules.is_safe(&grid, 0, 0, 2));
+    grid.set(0, 0, 2).unwrap();
+    assert!(!rules.is_safe(&grid, 0, 1, 2));
+    assert!(rules.is_safe(&grid, 0, 1, 1));
+    grid.set(0, 1, 3).unwrap();
+    assert!(!rules.is_satisfied(&grid));
+}
+
+#[test]
+fn cages_add_up_the_houses_of_their_rules() {
+    // The cage fills half of the first box, but the rest of that box does
+    // not make up the remaining 7 here: only the jigsaw region does.
+    let regions: Regions = "1112 3122 3342 3444".parse().unwrap();
+    let shape = regions.shape();
+    let cages = Cages::new(shape, vec![Cage::new(3, &[0, 1])]).unwrap();
+    let solution: Grid = "1234 2413 3142 4321".parse().unwrap();
+    let mut givens = solution.clone();
+    givens.clear(1, 0);
+    givens.clear(1, 1);
+    let solver = Solver::with_rules(Rules::jigsaw(regions).with(cages.clone()));
+    assert_eq!(solver.solve(&givens), Some(solution));
+    assert_eq!(solver.count(&givens), 1);
+    assert_eq!(
+        Solver::with_rules(Rules::classic().with(cages)).count(&givens),
+        0
+    );
+}
+
+#[test]
+fn generated_killers_are_unique_without_givens() {
+    let shape = Shape::new(2, 3).unwrap();
+    let options = KillerOptions::new().shape(shape).max_cage(4);
+    let mut generator = Generator::from_seed(24);
+    for _ in 0..3 {
+        let puzzle = generator.generate_killer(&options).unwrap();
+        assert_eq!(puzzle.givens(), &Grid::new(shape));
+        assert!(puzzle.cages().cages().iter().all(|c| c.cells().len() <= 4));
+        assert_cages_hold(&puzzle);
+
+        let solver = Solver::with_rules(puzzle.rules());
+        assert!(solver.has_unique_solution(puzzle.givens()));
+        assert_eq!(
+            solver.solve(puzzle.givens()).as_ref(),
+            Some(puzzle.solution())
+        );
+        let rebuilt = KillerPuzzle::new(puzzle.cages().clone(), puzzle.givens().clone());
+        assert_eq!(rebuilt.as_ref(), Some(&puzzle));
+    }
+}
+
+#[test]
+fn classic_killers_keep_their_givens() {
+    let options = KillerOptions::new().givens(30);
+    let puzzle = Generator::from_seed(25).generate_killer(&options).unwrap();
+    assert_eq!(puzzle.givens().filled_count(), 30);
+    assert_cages_hold(&puzzle);
+    assert!(Solver::with_rules(puzzle.rules()).has_unique_solution(puzzle.givens()));
+    assert!(Generator::new()
+        .generate_killer(&KillerOptions::new().givens(82))
+        .is_err());
+}
+
+#[test]
+fn svg_draws_dashed_cages_with_sums() {
+    let shape = Shape::new(2, 2).unwrap();
+    let cages = Cages::new(
+        shape,
+        vec![Cage::new(3, &[0, 1]), Cage::new(10, &[2, 3, 7, 11])],
+    )
+    .unwrap();
+    let svg = SvgRenderer::new()
+        .cages(&cages)
+        .cage_color("#aa0000")
+        .render(&Grid::new(shape));
+    assert_eq!(svg.matches("stroke-dasharray").count(), 2);
+    assert!(svg.contains("stroke=\"#aa0000\""));
+    assert!(svg.contains(">3</text>"));
+    assert!(svg.contains(">10</text>"));
+}
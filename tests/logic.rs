@@ -0,0 +1,46 @@
+use sudoku::{Candidates, Generator, LogicalSolver, PuzzleOptions, Technique};
+
+#[test]
+fn every_deduction_agrees_with_the_solution() {
+    let mut generator = Generator::from_seed(9);
+    let solver = LogicalSolver::new();
+    let mut used = Vec::new();
+    for _ in 0..40 {
+        let puzzle = generator.generate(&PuzzleOptions::new(24)).unwrap();
+        let solution = puzzle.solution();
+        let path = solver.solve(puzzle.givens()).unwrap();
+        for step in &path.steps {
+            if let Some((row, col, digit)) = step.placement {
+                assert_eq!(solution.get(row, col), Some(digit), "{}", step);
+            }
+            for &(row, col, digit) in &step.eliminations {
+                assert_ne!(solution.get(row, col), Some(digit), "{}", step);
+            }
+            used.push(step.technique);
+        }
+        if path.is_solved() {
+            assert_eq!(&path.grid, solution);
+        }
+    }
+    assert!(used.contains(&Technique::HiddenSingle));
+    assert!(used
+        .iter()
+        .any(|&technique| technique > Technique::HiddenSingle));
+}
+
+#[test]
+fn singles_alone_solve_an_easy_puzzle() {
+    let puzzle = Generator::from_seed(10)
+        .generate(&PuzzleOptions::new(50))
+        .unwrap();
+    let solver = LogicalSolver::with_techniques(&[Technique::NakedSingle, Technique::HiddenSingle]);
+    let path = solver.solve(puzzle.givens()).unwrap();
+    assert!(path.is_solved());
+
+    let mut candidates = Candidates::new(puzzle.givens()).unwrap();
+    for step in &path.steps {
+        assert_eq!(solver.next_step(&candidates).as_ref(), Some(step));
+        candidates.apply(step);
+    }
+    assert!(candidates.is_solved());
+}
@@ -0,0 +1,56 @@
+use sudoku::{rate, rate_with, weight, LogicalSolver, Rating, Technique, Tier, STUCK_PENALTY};
+
+/** Solved by singles alone. */
+const SINGLES: &str =
+    "81..5...2...8...9..5......1.69..5......1.....2...3.71......29.3...519......7...64";
+/** Needs one pointing pair. */
+const POINTING_PAIR: &str =
+    "...3...2.2....58....6..4.9.....624.99.7.....8......6......59....8...61.7..37.....";
+/** Needs one naked pair. */
+const NAKED_PAIR: &str =
+    "..9..76..7.49...8.2...8.1....8.9...39...4.21...38........6....5...4.........1593.";
+/** Gets the logical solver stuck. */
+const STUCK: &str =
+    ".1..5.69..4.........86.41..7..4.1962..2..54.........7..9..3...6...1.2.....3....5.";
+
+/** Returns the score of a rating recomputed from its step counts. */
+fn weighed(rating: &Rating) -> u32 {
+    let steps: u32 = rating
+        .counts
+        .iter()
+        .map(|(&technique, &count)| weight(technique) * count as u32)
+        .sum();
+    20 * rating.hardest.map_or(0, weight) + steps
+}
+
+#[test]
+fn the_hardest_technique_decides_the_tier() {
+    let cases = [
+        (SINGLES, Tier::Easy, Technique::HiddenSingle),
+        (POINTING_PAIR, Tier::Medium, Technique::PointingPair),
+        (NAKED_PAIR, Tier::Hard, Technique::NakedPair),
+    ];
+    for (puzzle, tier, hardest) in cases {
+        let rating = rate(&puzzle.parse().unwrap()).unwrap();
+        assert!(rating.solved);
+        assert_eq!(rating.tier, tier);
+        assert_eq!(rating.hardest, Some(hardest));
+        assert_eq!(Tier::of(hardest), tier);
+        assert_eq!(rating.score, weighed(&rating));
+    }
+}
+
+#[test]
+fn stuck_puzzles_are_diabolical() {
+    let rating = rate(&STUCK.parse().unwrap()).unwrap();
+    assert!(!rating.solved);
+    assert_eq!(rating.tier, Tier::Diabolical);
+    assert_eq!(rating.score, weighed(&rating) + STUCK_PENALTY);
+
+    let singles =
+        LogicalSolver::with_techniques(&[Technique::NakedSingle, Technique::HiddenSingle]);
+    let rating = rate_with(&singles, &POINTING_PAIR.parse().unwrap()).unwrap();
+    assert_eq!(rating.tier, Tier::Diabolical);
+    assert_eq!(rating.score, weighed(&rating) + STUCK_PENALTY);
+    assert!(rate(&format!("11{}", ".".repeat(79)).parse().unwrap()).is_none());
+}
@@ -0,0 +1,39 @@
+use sudoku::{Grid, Renderer, Solver, Style};
+
+const PUZZLE: &str =
+    "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+#[test]
+fn ascii_frames_boxes_and_shows_blanks() {
+    let grid: Grid = PUZZLE.parse().unwrap();
+    let text = Renderer::new().style(Style::Ascii).blank(' ').render(&grid);
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 13);
+    assert_eq!(lines[0], "+-------+-------+-------+");
+    assert_eq!(lines[1], "| 4     |       | 8   5 |");
+    assert_eq!(lines[4], lines[0]);
+    assert_eq!(lines[5], "|   2   |       |   6   |");
+    assert!(!text.contains('\x1b'));
+
+    let unicode = Renderer::new().render(&grid);
+    assert!(unicode.starts_with("┌───────┬"));
+    assert!(unicode.contains("│ 4 . . │ . . . │ 8 . 5 │\n"));
+    assert!(unicode.ends_with("┴───────┘\n"));
+}
+
+#[test]
+fn colors_tell_givens_solved_and_conflicts_apart() {
+    let givens: Grid = PUZZLE.parse().unwrap();
+    let solution = Solver::new().solve(&givens).unwrap();
+    let renderer = Renderer::new().color(true);
+
+    let solved = renderer.render_solved(&givens, &solution);
+    assert!(solved.contains("\x1b[1m4\x1b[0m"));
+    assert!(solved.contains("\x1b[36m1\x1b[0m"));
+    assert!(!solved.contains("\x1b[1;31m"));
+
+    let mut broken = givens;
+    broken.set(0, 1, 8).unwrap();
+    let conflicting = renderer.render(&broken);
+    assert_eq!(conflicting.matches("\x1b[1;31m8").count(), 2);
+}
@@ -0,0 +1,120 @@
+use sudoku::{
+    has_unique_solution, parse, parse_many, parse_shaped, Format, Generator, Grid, GridError,
+    ParseErrorKind, PuzzleOptions, Renderer, Shape, Solver, Style, SvgRenderer, Symmetry,
+};
+
+#[test]
+fn shapes_parse_and_pick_the_usual_boxes() {
+    assert_eq!("2x3".parse(), Ok(Shape::new(2, 3).unwrap()));
+    assert_eq!("12".parse(), Ok(Shape::new(3, 4).unwrap()));
+    assert_eq!(Shape::for_size(16), Shape::new(4, 4).ok());
+    assert_eq!(Shape::for_size(7), None);
+    assert_eq!(
+        Shape::new(5, 6),
+        Err(GridError::InvalidShape {
+            box_rows: 5,
+            box_cols: 6
+        })
+    );
+    assert!("1x4".parse::<Shape>().is_err());
+    assert_eq!(Shape::new(3, 4).unwrap().to_string(), "3x4");
+}
+
+#[test]
+fn every_size_generates_unique_puzzles() {
+    for (rows, cols, clues) in [(2, 2, 6), (2, 3, 14), (3, 4, 70), (4, 4, 130)] {
+        let shape = Shape::new(rows, cols).unwrap();
+        let puzzle = Generator::from_seed(21)
+            .generate(
+                &PuzzleOptions::new(clues)
+                    .shape(shape)
+                    .symmetry(Symmetry::Rotational180),
+            )
+            .unwrap();
+        let givens = puzzle.givens();
+        assert_eq!(givens.shape(), shape);
+        assert_eq!(givens.filled_count(), clues, "{}", shape);
+        assert!(has_unique_solution(givens), "{}", shape);
+        assert_eq!(
+            Solver::new().solve(givens).as_ref(),
+            Some(puzzle.solution())
+        );
+        assert!(puzzle.solution().is_complete());
+    }
+}
+
+#[test]
+fn rectangular_boxes_keep_their_orientation() {
+    let shape = Shape::new(2, 3).unwrap();
+    let solution = Generator::from_seed(22).solution_with_shape(shape);
+    for band in 0..3 {
+        for stack in 0..2 {
+            let mut digits: Vec<u8> = (0..6)
+                .map(|i| solution.get(band * 2 + i / 3, stack * 3 + i % 3).unwrap())
+                .collect();
+            digits.sort();
+            assert_eq!(digits, [1, 2, 3, 4, 5, 6]);
+        }
+    }
+}
+
+#[test]
+fn digits_above_nine_are_letters() {
+    let shape = Shape::new(4, 4).unwrap();
+    let solution = Generator::from_seed(23).solution_with_shape(shape);
+    let text = Format::Line.write(&solution);
+    assert!(text.contains('G') && !text.contains('0'));
+    assert_eq!(parse(&text.to_lowercase()).as_ref(), Ok(&solution));
+    for format in Format::ALL {
+        let text = format.write(&solution);
+        assert_eq!(parse(&text).as_ref(), Ok(&solution), "{}", format);
+        assert_eq!(parse_shaped(&text, shape).as_ref(), Ok(&solution));
+    }
+    let rows: Vec<String> = solution.to_string().lines().map(str::to_string).collect();
+    assert_eq!(rows.len(), 16);
+    assert_eq!(rows[0].split(' ').count(), 16);
+}
+
+#[test]
+fn sizes_are_inferred_from_the_text() {
+    let four = "1.3.\n..1.\n.1..\n3.2.\n";
+    let grid = parse(four).unwrap();
+    assert_eq!(grid.shape(), Shape::new(2, 2).unwrap());
+    assert_eq!(grid.get(3, 2), Some(2));
+    assert_eq!(parse("1.3...1..1..3.2.").as_ref(), Ok(&grid));
+    assert_eq!(
+        parse("5...............").unwrap_err().kind,
+        ParseErrorKind::DigitOutOfRange('5')
+    );
+    assert_eq!(
+        parse(&".".repeat(35)).unwrap_err().kind,
+        ParseErrorKind::TooFewCells(35)
+    );
+
+    let six: Grid = format!("12{}", ".".repeat(34)).parse().unwrap();
+    let nine: Grid = format!("12{}", ".".repeat(79)).parse().unwrap();
+    let text = format!(
+        "{}{}\n{}",
+        Format::Line.write(&six),
+        Format::SimpleSudoku.write(&grid),
+        Format::Line.write(&nine)
+    );
+    let parsed: Vec<Grid> = parse_many(&text).into_iter().map(Result::unwrap).collect();
+    assert_eq!(parsed, [six, grid, nine]);
+}
+
+#[test]
+fn renderers_draw_the_box_outlines() {
+    let shape = Shape::new(2, 3).unwrap();
+    let solution = Generator::from_seed(24).solution_with_shape(shape);
+    let drawing = Renderer::new().style(Style::Ascii).render(&solution);
+    let lines: Vec<&str> = drawing.lines().collect();
+    assert_eq!(lines.len(), 6 + 4);
+    assert_eq!(lines[0], "+-------+-------+");
+    assert_eq!(lines[3], lines[0]);
+    assert_eq!(lines[1].matches('|').count(), 3);
+
+    let svg = SvgRenderer::new().cell_size(40.0).render(&solution);
+    assert!(svg.contains("width=\"245\""));
+    assert_eq!(svg.matches("<text ").count(), 36);
+}
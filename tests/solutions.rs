@@ -0,0 +1,69 @@
+use std::collections::HashSet;
+
+use sudoku::{count_solutions, solutions, Generator, Grid, PuzzleOptions, Solver};
+
+#[test]
+fn solutions_match_the_count_and_are_distinct() {
+    let mut generator = Generator::from_seed(19);
+    let puzzle = generator.generate(&PuzzleOptions::new(30)).unwrap();
+    let mut givens = puzzle.givens().clone();
+    let mut cleared = 0;
+    for row in 0..9 {
+        for col in 0..9 {
+            if cleared < 6 && givens.get(row, col).is_some() {
+                givens.clear(row, col);
+                cleared += 1;
+            }
+        }
+    }
+    let found: Vec<Grid> = solutions(&givens).collect();
+    assert_eq!(found.len(), count_solutions(&givens, usize::MAX));
+    assert!(found.len() > 1);
+    assert!(found.contains(puzzle.solution()));
+    let distinct: HashSet<_> = found.iter().collect();
+    assert_eq!(distinct.len(), found.len());
+    for solution in &found {
+        assert!(solution.is_complete());
+        assert_eq!(Solver::new().solve(solution).as_ref(), Some(solution));
+        for row in 0..9 {
+            for col in 0..9 {
+                if let Some(digit) = givens.get(row, col) {
+                    assert_eq!(solution.get(row, col), Some(digit));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn enumeration_is_lazy() {
+    let empty = Grid::empty();
+    let first: Vec<Grid> = solutions(&empty).take(3).collect();
+    assert_eq!(first.len(), 3);
+    assert_ne!(first[0], first[1]);
+    assert_eq!(solutions(&empty).next(), Solver::new().solve(&empty));
+
+    let mut iter = solutions(&empty).skip(1000);
+    assert!(iter.next().is_some());
+}
+
+#[test]
+fn edge_cases_yield_the_right_number_of_solutions() {
+    let mut generator = Generator::from_seed(20);
+    let puzzle = generator.generate(&PuzzleOptions::new(28)).unwrap();
+    assert_eq!(
+        solutions(puzzle.givens()).collect::<Vec<_>>(),
+        [puzzle.solution().clone()]
+    );
+    assert_eq!(
+        solutions(puzzle.solution()).collect::<Vec<_>>(),
+        [puzzle.solution().clone()]
+    );
+
+    let mut conflict = Grid::empty();
+    conflict.set(0, 0, 5).unwrap();
+    conflict.set(0, 8, 5).unwrap();
+    let mut none = solutions(&conflict);
+    assert_eq!(none.next(), None);
+    assert_eq!(none.next(), None);
+}
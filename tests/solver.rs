@@ -0,0 +1,133 @@
+use sudoku::{count_solutions, has_unique_solution, Generator, Grid, PuzzleOptions, Shape, Solver};
+
+/** Counts solutions the way the solver did before candidate masks. */
+fn naive_count(grid: &mut Grid) -> usize {
+    let Some((row, col)) = grid.find_empty() else {
+        return 1;
+    };
+    let mut count = 0;
+    for digit in 1..=grid.size() as u8 {
+        if grid.is_safe(row, col, digit) {
+            grid.set(row, col, digit).unwrap();
+            count += naive_count(grid);
+            grid.clear(row, col);
+        }
+    }
+    count
+}
+
+#[test]
+fn conflicting_givens_have_no_solution() {
+    let solver = Solver::new();
+    for (a, b) in [((0, 0), (0, 8)), ((0, 4), (8, 4)), ((3, 3), (5, 5))] {
+        let mut grid = Grid::empty();
+        grid.set(a.0, a.1, 5).unwrap();
+        grid.set(b.0, b.1, 5).unwrap();
+        assert_eq!(solver.solve(&grid), None);
+        assert_eq!(solver.count_solutions(&grid, 2), 0);
+        assert_eq!(solver.solutions(&grid).next(), None);
+    }
+}
+
+#[test]
+fn digits_with_no_place_left_end_the_search() {
+    // Every blank of the first row sees a 16, yet still has a dozen
+    // candidates: searching instead of spotting this would take 12! steps.
+    let shape = Shape::new(4, 4).unwrap();
+    let mut grid = Grid::new(shape);
+    for (row, col, digit) in [
+        (1, 0, 16),
+        (2, 4, 16),
+        (3, 8, 16),
+        (4, 15, 16),
+        (0, 12, 1),
+        (0, 13, 2),
+        (0, 14, 3),
+    ] {
+        grid.set(row, col, digit).unwrap();
+    }
+    let solver = Solver::new();
+    assert_eq!(solver.solve(&grid), None);
+    assert_eq!(solver.count_solutions(&grid, 1), 0);
+}
+
+#[test]
+fn counts_match_a_naive_search() {
+    let mut generator = Generator::from_seed(3);
+    let solver = Solver::new();
+    let mut counts = Vec::new();
+    for blanks in [0, 5, 10] {
+        let puzzle = generator.generate(&PuzzleOptions::new(45)).unwrap();
+        let mut grid = puzzle.givens().clone();
+        let filled: Vec<(usize, usize)> = (0..81)
+            .map(|cell| (cell / 9, cell % 9))
+            .filter(|&(row, col)| grid.get(row, col).is_some())
+            .collect();
+        for &(row, col) in filled.iter().take(blanks) {
+            grid.clear(row, col);
+        }
+        let count = solver.count(&grid);
+        assert_eq!(count, naive_count(&mut grid.clone()));
+        counts.push(count);
+    }
+    assert_eq!(counts[0], 1);
+    assert!(counts[2] > 1, "{:?}", counts);
+    let small = Grid::new(Shape::new(2, 2).unwrap());
+    assert_eq!(solver.count(&small), naive_count(&mut small.clone()));
+    assert_eq!(solver.count(&small), 288);
+}
+
+/**
+ * Blanks four cells of a filled grid that hold two digits crosswise in two
+ * boxes, which the digits can then swap between: exactly two solutions.
+ */
+fn blank_rectangle(grid: &mut Grid) -> bool {
+    for r1 in 0..9 {
+        for r2 in r1 + 1..r1 / 3 * 3 + 3 {
+            for c1 in 0..9 {
+                for c2 in (c1 / 3 + 1) * 3..9 {
+                    let (a, b) = (grid.get(r1, c1), grid.get(r1, c2));
+                    if grid.get(r2, c1) == b && grid.get(r2, c2) == a {
+                        for (row, col) in [(r1, c1), (r1, c2), (r2, c1), (r2, c2)] {
+                            grid.clear(row, col);
+                        }
+                        return true;
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+#[test]
+fn counting_stops_at_the_limit() {
+    let solver = Solver::new();
+    let empty = Grid::empty();
+    for limit in [0, 1, 2, 5] {
+        assert_eq!(solver.count_solutions(&empty, limit), limit);
+        assert_eq!(count_solutions(&empty, limit), limit);
+    }
+    assert!(!solver.has_unique_solution(&empty));
+    assert_eq!(solver.solve_count(&empty, 0), (None, 0));
+}
+
+#[test]
+fn uniqueness_tells_one_solution_from_two() {
+    let mut generator = Generator::from_seed(2);
+    let mut twice = None;
+    while twice.is_none() {
+        let puzzle = generator.generate(&PuzzleOptions::new(30)).unwrap();
+        assert!(has_unique_solution(puzzle.givens()));
+        let mut grid = puzzle.solution().clone();
+        if blank_rectangle(&mut grid) {
+            twice = Some(grid);
+        }
+    }
+    let twice = twice.unwrap();
+    let solver = Solver::new();
+    assert_eq!(solver.count_solutions(&twice, usize::MAX), 2);
+    assert_eq!(solver.count_solutions(&twice, 1), 1);
+    assert!(!solver.has_unique_solution(&twice));
+    assert!(!has_unique_solution(&twice));
+}
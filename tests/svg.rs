@@ -0,0 +1,47 @@
+use sudoku::{Candidates, Grid, Solver, SvgRenderer};
+
+const PUZZLE: &str =
+    "4.....8.5.3..........7......2.....6.....8.4......1.......6.3.7.5..2.....1.4......";
+
+#[test]
+fn givens_are_bold_and_solved_digits_are_not() {
+    let givens: Grid = PUZZLE.parse().unwrap();
+    let solution = Solver::new().solve(&givens).unwrap();
+    let svg = SvgRenderer::new()
+        .cell_size(50.0)
+        .font_family("Georgia, \"Times\"")
+        .render_solved(&givens, &solution);
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"456.25\""));
+    assert!(svg.ends_with("</svg>\n"));
+    assert!(svg.contains("font-family=\"Georgia, &quot;Times&quot;\""));
+    assert_eq!(svg.matches("<text ").count(), 81);
+    assert_eq!(
+        svg.matches("font-weight=\"bold\"").count(),
+        givens.filled_count()
+    );
+    assert_eq!(svg.matches("<path ").count(), 2);
+}
+
+#[test]
+fn pencil_marks_show_candidates_of_blank_cells() {
+    let givens: Grid = PUZZLE.parse().unwrap();
+    let plain = SvgRenderer::new().render(&givens);
+    assert_eq!(plain.matches("<text ").count(), givens.filled_count());
+
+    let candidates = Candidates::new(&givens).unwrap();
+    let marks: usize = (0..9)
+        .flat_map(|row| (0..9).map(move |col| (row, col)))
+        .map(|(row, col)| candidates.candidates(row, col).len())
+        .sum();
+    let marked = SvgRenderer::new()
+        .pencil_marks(true)
+        .mark_color("red")
+        .render(&givens);
+    assert_eq!(marked.matches("fill=\"red\"").count(), marks);
+    assert_eq!(
+        SvgRenderer::new()
+            .mark_color("red")
+            .render_candidates(&givens, &candidates),
+        marked
+    );
+}
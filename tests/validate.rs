@@ -0,0 +1,185 @@
+use sudoku::{
+    validate, validate_rows, validate_with, Cage, Cages, Generator, Grid, PuzzleOptions, Regions,
+    Rules, Shape, SolutionCount, Unit, Validation, Violation,
+};
+
+#[test]
+fn duplicates_name_every_unit_and_cell() {
+    let mut grid = Grid::empty();
+    grid.set(0, 0, 7).unwrap();
+    grid.set(0, 5, 7).unwrap();
+    grid.set(2, 2, 7).unwrap();
+    grid.set(8, 0, 7).unwrap();
+    let validation = validate(&grid);
+    assert_eq!(
+        validation.violations,
+        [
+            Violation::Duplicate {
+                digit: 7,
+                unit: Unit::Row(0),
+                cells: vec![(0, 0), (0, 5)],
+            },
+            Violation::Duplicate {
+                digit: 7,
+                unit: Unit::Column(0),
+                cells: vec![(0, 0), (8, 0)],
+            },
+            Violation::Duplicate {
+                digit: 7,
+                unit: Unit::Box(0),
+                cells: vec![(0, 0), (2, 2)],
+            },
+        ]
+    );
+    assert_eq!(validation.solutions, Some(SolutionCount::Zero));
+    assert!(!validation.is_valid());
+    assert_eq!(
+        validation.violations[0].to_string(),
+        "7 appears 2 times in row 1 at r1c1, r1c6"
+    );
+}
+
+#[test]
+fn solution_counts_are_reported() {
+    let mut generator = Generator::from_seed(20);
+    let puzzle = generator.generate(&PuzzleOptions::new(30)).unwrap();
+    assert!(validate(puzzle.givens()).is_valid());
+    assert_eq!(
+        validate(&Grid::empty()),
+        Validation {
+            violations: Vec::new(),
+            solutions: Some(SolutionCount::Multiple),
+        }
+    );
+}
+
+#[test]
+fn raw_input_reports_shape_and_range() {
+    let mut rows = vec![vec![0u8; 9]; 8];
+    rows[0][0] = 12;
+    rows[1] = vec![3, 0, 3];
+    rows[2].push(4);
+    let validation = validate_rows(Shape::CLASSIC, &rows);
+    assert_eq!(validation.solutions, None);
+    assert_eq!(
+        validation.violations,
+        [
+            Violation::RowCount {
+                expected: 9,
+                found: 8,
+            },
+            Violation::OutOfRange {
+                row: 0,
+                col: 0,
+                value: 12,
+                size: 9,
+            },
+            Violation::RowLength {
+                row: 1,
+                expected: 9,
+                found: 3,
+            },
+            Violation::RowLength {
+                row: 2,
+                expected: 9,
+                found: 10,
+            },
+            Violation::Duplicate {
+                digit: 3,
+                unit: Unit::Row(1),
+                cells: vec![(1, 0), (1, 2)],
+            },
+            Violation::Duplicate {
+                digit: 3,
+                unit: Unit::Box(0),
+                cells: vec![(1, 0), (1, 2)],
+            },
+        ]
+    );
+
+    let mut generator = Generator::from_seed(21);
+    let puzzle = generator.generate(&PuzzleOptions::new(30)).unwrap();
+    let values = puzzle.givens().to_values();
+    let rows: Vec<&[u8]> = values.chunks(9).collect();
+    assert_eq!(
+        validate_rows(Shape::CLASSIC, &rows),
+        validate(puzzle.givens())
+    );
+}
+
+#[test]
+fn raw_input_is_checked_against_its_shape() {
+    let shape = Shape::new(2, 3).unwrap();
+    let mut rows = vec![vec![0u8; 6]; 6];
+    rows[0][0] = 6;
+    rows[5][5] = 7;
+    let validation = validate_rows(shape, &rows);
+    assert_eq!(
+        validation.violations,
+        [Violation::OutOfRange {
+            row: 5,
+            col: 5,
+            value: 7,
+            size: 6,
+        }]
+    );
+    assert_eq!(
+        validation.violations[0].to_string(),
+        "value 7 at r6c6 is outside 0..=6"
+    );
+
+    rows[5][5] = 0;
+    assert_eq!(
+        validate_rows(shape, &rows).solutions,
+        Some(SolutionCount::Multiple)
+    );
+    let large = vec![vec![0u8; 16]; 16];
+    let validation = validate_rows(Shape::new(4, 4).unwrap(), &large);
+    assert!(validation.violations.is_empty());
+    assert_eq!(validation.solutions, Some(SolutionCount::Multiple));
+}
+
+#[test]
+fn variants_are_validated_under_their_rules() {
+    let mut grid = Grid::empty();
+    grid.set(0, 0, 5).unwrap();
+    grid.set(8, 8, 5).unwrap();
+    assert!(validate(&grid).violations.is_empty());
+    let validation = validate_with(&grid, &Rules::diagonal());
+    assert_eq!(
+        validation.violations,
+        [Violation::Duplicate {
+            digit: 5,
+            unit: Unit::Diagonal(0),
+            cells: vec![(0, 0), (8, 8)],
+        }]
+    );
+    assert_eq!(validation.solutions, Some(SolutionCount::Zero));
+
+    let regions: Regions = "1112 3122 3342 3444".parse().unwrap();
+    let mut grid = Grid::new(regions.shape());
+    grid.set(0, 2, 3).unwrap();
+    grid.set(1, 1, 3).unwrap();
+    assert!(validate(&grid).violations.is_empty());
+    let validation = validate_with(&grid, &Rules::jigsaw(regions));
+    assert_eq!(
+        validation.violations[0].to_string(),
+        "3 appears 2 times in region 1 at r1c3, r2c2"
+    );
+
+    let cages = Cages::new(Shape::CLASSIC, vec![Cage::new(3, &[0, 1])]).unwrap();
+    let mut grid = Grid::empty();
+    grid.set(0, 0, 1).unwrap();
+    grid.set(0, 1, 4).unwrap();
+    let validation = validate_with(&grid, &Rules::classic().with(cages));
+    assert_eq!(
+        validation.violations,
+        [Violation::Unsatisfied {
+            rule: "cages".to_string()
+        }]
+    );
+    assert_eq!(
+        validation.violations[0].to_string(),
+        "the cages rule is broken"
+    );
+}